@@ -1639,6 +1639,42 @@ impl AgentPanel {
         }
     }
 
+    /// Focuses the panel and inserts `text` into the message of its active thread, for views
+    /// outside this crate with text to add as context. Returns whether there was a thread.
+    pub fn insert_text_into_thread(
+        workspace: &mut Workspace,
+        text: String,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> bool {
+        let Some(agent_panel) = workspace
+            .panel::<Self>(cx)
+            .filter(|panel| panel.read(cx).enabled(cx))
+        else {
+            return false;
+        };
+        if agent_panel.read(cx).active_conversation_view().is_none() {
+            return false;
+        }
+        workspace.focus_panel::<Self>(window, cx);
+        agent_panel.update(cx, |_, cx| {
+            cx.defer_in(window, move |panel, window, cx| {
+                let active_thread = panel
+                    .active_conversation_view()
+                    .and_then(|conversation_view| conversation_view.read(cx).active_thread())
+                    .cloned();
+                if let Some(active_thread) = active_thread {
+                    active_thread.update(cx, |thread, cx| {
+                        thread.active_editor(cx).update(cx, |editor, cx| {
+                            editor.insert_text(&text, window, cx);
+                        })
+                    });
+                }
+            });
+        });
+        true
+    }
+
     pub fn thread_store(&self) -> &Entity<ThreadStore> {
         &self.thread_store
     }
@@ -199,6 +199,7 @@ impl VsCodeSettings {
             language_models: None,
             line_indicator_format: None,
             log: None,
+            log_view: None,
             node: self.node_binary_settings(),
 
             outline_panel: self.outline_panel_settings_content(),
@@ -230,6 +230,9 @@ pub struct SettingsContent {
     /// Example: {"log": {"client": "warn"}}
     pub log: Option<HashMap<String, String>>,
 
+    /// Settings for the log view opened by `zed: open log`.
+    pub log_view: Option<LogViewSettingsContent>,
+
     pub line_indicator_format: Option<LineIndicatorFormat>,
 
     pub language_models: Option<AllLanguageModelSettingsContent>,
@@ -1211,6 +1214,123 @@ pub struct MarkdownPreviewSettingsContent {
     pub max_width: Option<f32>,
 }
 
+/// Settings for the log view.
+#[with_fallible_options]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, Default, PartialEq)]
+pub struct LogViewSettingsContent {
+    /// The maximum number of log lines kept in the log view.
+    /// Older lines are dropped once this limit is reached.
+    ///
+    /// Default: 1000
+    pub max_lines: Option<usize>,
+    /// The maximum number of bytes of log text kept in the log view, for logs
+    /// with very long lines. Older lines are dropped once either this or
+    /// `max_lines` is reached. When null, only `max_lines` applies.
+    ///
+    /// Default: null
+    pub max_bytes: Option<usize>,
+    /// How often, in milliseconds, the log files are checked for new lines.
+    /// Clamped with [10, 10000] range.
+    ///
+    /// Default: 100
+    pub poll_interval_ms: Option<u64>,
+    /// Named filters that can be applied from the log view's toolbar, in
+    /// addition to the built-in ones.
+    ///
+    /// Default: []
+    pub filter_presets: Option<Vec<LogFilterPresetContent>>,
+    /// The number of lines shown before and after the selected line in the
+    /// log view's context pane, whether or not they match the filter.
+    /// Clamped with [1, 100] range.
+    ///
+    /// Default: 5
+    pub context_line_count: Option<usize>,
+    /// The number of characters of a log line shown before it's cut short,
+    /// as very long lines are slow to render. Cut lines can be shown in full
+    /// from the log view.
+    /// Clamped with [100, 1000000] range.
+    ///
+    /// Default: 4000
+    pub max_line_length: Option<usize>,
+    /// Whether to show a notification when errors are logged while the log
+    /// view is following the end of the log.
+    ///
+    /// Default: true
+    pub notify_on_error: Option<bool>,
+    /// A regular expression matching the text between log records, for logs
+    /// whose records span several lines. Each record becomes one entry in the
+    /// log view. When unset, each line is an entry, with indented lines and
+    /// lines without a level attached to the entry before them. A record is
+    /// shown once the separator after it has been written.
+    ///
+    /// Default: null
+    pub record_separator: Option<String>,
+    /// Whether jumping to an entry, such as the next match or error, eases
+    /// the log view there rather than moving instantly. Jumps are always
+    /// instant when the system asks for reduced motion.
+    ///
+    /// Default: true
+    pub smooth_scroll: Option<bool>,
+    /// The language written after the opening fence when copying log lines
+    /// as a Markdown code block. Leave empty for a plain fence.
+    ///
+    /// Default: "log"
+    pub code_block_language: Option<String>,
+    /// Colors for lines at each level, such as "#ff5555", overriding the
+    /// theme's. Levels without a color use the theme's.
+    ///
+    /// Default: {}
+    pub level_colors: Option<LogViewLevelColorsContent>,
+    /// The most lines at the end of the previous session's log that are
+    /// checked for at the start of the current one. Lines written to both
+    /// around a rotation are only shown once. Set to 0 to show them twice.
+    /// Clamped with [0, 1000] range.
+    ///
+    /// Default: 50
+    pub rotation_overlap_line_count: Option<usize>,
+    /// The number of seconds between the timestamps of adjacent entries past
+    /// which a marker showing the gap is drawn between them. Set to 0 to hide
+    /// the markers.
+    ///
+    /// Default: 60
+    pub time_gap_threshold_secs: Option<u64>,
+}
+
+/// Colors for the lines at each level in the log view, as hex strings.
+#[with_fallible_options]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, Default, PartialEq)]
+pub struct LogViewLevelColorsContent {
+    pub error: Option<String>,
+    pub warn: Option<String>,
+    pub info: Option<String>,
+    pub debug: Option<String>,
+    pub trace: Option<String>,
+}
+
+/// A named filter that can be applied from the log view's toolbar.
+#[with_fallible_options]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+pub struct LogFilterPresetContent {
+    /// The name shown in the presets menu.
+    pub name: String,
+    /// The text put into the filter box.
+    #[serde(default)]
+    pub query: String,
+    /// The levels to show. All levels are shown when omitted.
+    pub levels: Option<Vec<LogViewLevel>>,
+}
+
+/// A log level that the log view can filter by.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogViewLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
 /// The settings for the image viewer.
 #[with_fallible_options]
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, Default, PartialEq)]
@@ -6,6 +6,7 @@ mod migrate;
 #[cfg(target_os = "macos")]
 pub(crate) mod move_to_applications;
 mod open_listener;
+pub mod open_log_view;
 mod open_url_modal;
 mod quick_action_bar;
 pub mod remote_debug;
@@ -23,7 +24,6 @@ use assets::Assets;
 
 use breadcrumbs::Breadcrumbs;
 use client::zed_urls;
-use collections::VecDeque;
 use debugger_ui::debugger_panel::DebugPanel;
 use editor::{Editor, MultiBuffer};
 use extension_host::ExtensionStore;
@@ -89,7 +89,7 @@ use theme_settings::{ThemeSettings, load_user_theme};
 use ui::{Navigable, NavigableEntry, PopoverMenuHandle, TintColor, prelude::*};
 use util::markdown::MarkdownString;
 use util::rel_path::RelPath;
-use util::{ResultExt, asset_str, maybe};
+use util::{ResultExt, asset_str};
 use uuid::Uuid;
 use vim_mode_setting::VimModeSetting;
 use workspace::notifications::{NotificationId, dismiss_app_notification, show_app_notification};
@@ -227,13 +227,29 @@ pub fn init(cx: &mut App) {
     if !crate::stdout_is_a_pty() {
         cx.on_action(|_: &OpenLog, cx| {
             with_active_or_new_workspace(cx, |workspace, window, cx| {
-                open_log_file(workspace, window, cx);
+                open_log_view::open(workspace, window, cx);
+            });
+        })
+        .on_action(|_: &open_log_view::OpenOldLog, cx| {
+            with_active_or_new_workspace(cx, |workspace, window, cx| {
+                open_log_view::open_old_log(workspace, window, cx);
+            });
+        })
+        .on_action(|action: &zed_actions::open_log::OpenFiltered, cx| {
+            let query = action.query.clone();
+            with_active_or_new_workspace(cx, move |workspace, window, cx| {
+                open_log_view::open_filtered(query, workspace, window, cx);
             });
         })
         .on_action(|_: &workspace::RevealLogInFileManager, cx| {
             cx.reveal_path(paths::log_file().as_path());
         });
     }
+    cx.on_action(|_: &open_log_view::TailFile, cx| {
+        with_active_or_new_workspace(cx, |workspace, window, cx| {
+            open_log_view::tail_file(workspace, window, cx);
+        });
+    });
 
     cx.on_action(|_: &zed_actions::OpenLicenses, cx| {
         with_active_or_new_workspace(cx, |workspace, window, cx| {
@@ -1438,6 +1454,8 @@ fn initialize_pane(
             let telemetry_log_item =
                 cx.new(|cx| telemetry_log::TelemetryLogToolbarItemView::new(window, cx));
             toolbar.add_item(telemetry_log_item, window, cx);
+            let open_log_item = cx.new(|cx| open_log_view::OpenLogToolbarItemView::new(window, cx));
+            toolbar.add_item(open_log_item, window, cx);
             let syntax_tree_item = cx.new(|_| language_tools::SyntaxTreeToolbarItemView::new());
             toolbar.add_item(syntax_tree_item, window, cx);
             let migration_banner =
@@ -1817,116 +1835,6 @@ fn quit(_: &Quit, cx: &mut App) {
     .detach_and_log_err(cx);
 }
 
-fn open_log_file(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
-    const MAX_LINES: usize = 1000;
-    let app_state = workspace.app_state();
-    let languages = app_state.languages.clone();
-    let fs = app_state.fs.clone();
-    cx.spawn_in(window, async move |workspace, cx| {
-        let log = {
-            let result = futures::join!(
-                fs.load(&paths::old_log_file()),
-                fs.load(&paths::log_file()),
-                languages.language_for_name("log")
-            );
-            match result {
-                (Err(_), Err(e), _) => Err(e),
-                (old_log, new_log, lang) => {
-                    let mut lines = VecDeque::with_capacity(MAX_LINES);
-                    for line in old_log
-                        .iter()
-                        .flat_map(|log| log.lines())
-                        .chain(new_log.iter().flat_map(|log| log.lines()))
-                    {
-                        if lines.len() == MAX_LINES {
-                            lines.pop_front();
-                        }
-                        lines.push_back(line);
-                    }
-                    Ok((
-                        lines
-                            .into_iter()
-                            .flat_map(|line| [line, "\n"])
-                            .collect::<String>(),
-                        lang.ok(),
-                    ))
-                }
-            }
-        };
-
-        let (log, log_language) = match log {
-            Ok((log, log_language)) => (log, log_language),
-            Err(e) => {
-                struct OpenLogError;
-
-                workspace
-                    .update(cx, |workspace, cx| {
-                        workspace.show_notification(
-                            NotificationId::unique::<OpenLogError>(),
-                            cx,
-                            |cx| {
-                                cx.new(|cx| {
-                                    MessageNotification::new(
-                                        format!(
-                                            "Unable to access/open log file at path \
-                                                    {}: {e:#}",
-                                            paths::log_file().display()
-                                        ),
-                                        cx,
-                                    )
-                                })
-                            },
-                        );
-                    })
-                    .ok();
-                return;
-            }
-        };
-        maybe!(async move {
-            let project = workspace
-                .read_with(cx, |workspace, _| workspace.project().clone())
-                .ok()?;
-            let buffer = project
-                .update(cx, |project, cx| {
-                    project.create_buffer(log_language, false, cx)
-                })
-                .await
-                .ok()?;
-            buffer.update(cx, |buffer, cx| {
-                buffer.set_capability(Capability::ReadOnly, cx);
-                buffer.set_text(log, cx);
-            });
-
-            let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx).with_title("Log".into()));
-
-            let editor = cx
-                .new_window_entity(|window, cx| {
-                    let mut editor = Editor::for_multibuffer(buffer, Some(project), window, cx);
-                    editor.set_read_only(true);
-                    editor.set_breadcrumb_header(format!(
-                        "Last {} lines in {}",
-                        MAX_LINES,
-                        paths::log_file().display()
-                    ));
-                    let last_multi_buffer_offset = editor.buffer().read(cx).len(cx);
-                    editor.change_selections(Default::default(), window, cx, |s| {
-                        s.select_ranges(Some(last_multi_buffer_offset..last_multi_buffer_offset));
-                    });
-                    editor
-                })
-                .ok()?;
-
-            workspace
-                .update_in(cx, |workspace, window, cx| {
-                    workspace.add_item_to_active_pane(Box::new(editor), None, true, window, cx);
-                })
-                .ok()
-        })
-        .await;
-    })
-    .detach();
-}
-
 fn notify_settings_errors(result: settings::SettingsParseResult, is_user: bool, cx: &mut App) {
     if let settings::ParseStatus::Failed { error: err } = &result.parse_status {
         let settings_type = if is_user { "user" } else { "global" };
@@ -0,0 +1,8164 @@
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::io::SeekFrom;
+use std::iter;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::time::{Duration, Instant};
+
+use anyhow::Context as _;
+use chrono::{DateTime, FixedOffset, Local, TimeDelta};
+use collections::{HashMap, HashSet};
+use db::kvp::KeyValueStore;
+use editor::Editor;
+use fs::Fs;
+use futures::{FutureExt as _, StreamExt};
+use fuzzy::{StringMatch, StringMatchCandidate};
+use gpui::{
+    Anchor, App, BackgroundExecutor, ClickEvent, ClipboardItem, Empty, Entity, EventEmitter,
+    FocusHandle, Focusable, FollowMode, FontWeight, ForegroundExecutor, HighlightStyle, Hsla,
+    InteractiveText, ListAlignment, ListOffset, ListState, PathPromptOptions, Pixels, PromptLevel,
+    Rgba, StyledText, Subscription, Task, UnderlineStyle, WeakEntity, Window, actions,
+    combine_highlights, ease_in_out, list, prelude::*,
+};
+use itertools::Either;
+use language::Point;
+use project::{DirectoryLister, Project, ResolvedPath, search::SearchQuery};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use settings::{
+    LogFilterPresetContent, LogViewLevel, RegisterSetting, SeedQuerySetting, Settings,
+    SettingsStore, update_settings_file,
+};
+use smol::io::{AsyncReadExt as _, AsyncSeekExt as _};
+use ui::{
+    Banner, CommonAnimationExt as _, ContextMenu, ContextMenuEntry, Disclosure, Icon, IconButton,
+    IconName, IconSize, Label, PopoverMenu, TextSize, Tooltip, WithScrollbar, prelude::*,
+};
+use util::ResultExt as _;
+use workspace::{
+    Item, ItemEvent, ItemHandle, ItemId, OpenOptions, OpenVisible, SerializableItem, Toast,
+    ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView, Workspace, WorkspaceId,
+    delete_unloaded_items,
+    notifications::NotificationId,
+    searchable::{
+        Direction, SearchEvent, SearchOptions, SearchToken, SearchableItem, SearchableItemHandle,
+    },
+};
+
+use persistence::OpenLogViewDb;
+
+actions!(
+    open_log,
+    [
+        /// Scrolls to the next log line matching the filter query.
+        SelectNextMatch,
+        /// Scrolls to the previous log line matching the filter query.
+        SelectPreviousMatch,
+        /// Removes all lines from the log view without touching the log file.
+        ClearView,
+        /// Loads the log files again from disk, replacing the lines shown.
+        ReloadLog,
+        /// Opens the log file of the previous session in its own log view.
+        OpenOldLog,
+        /// Prompts for a file, then follows it in a log view like Zed's own log.
+        TailFile,
+        /// Deletes the contents of the log file after asking for confirmation.
+        TruncateLogFile,
+        /// Moves focus to the log filter box and selects its contents.
+        FocusFilter,
+        /// Empties the log filter box, showing all lines again.
+        ClearFilter,
+        /// Increases the size of the log text.
+        ZoomIn,
+        /// Decreases the size of the log text.
+        ZoomOut,
+        /// Restores the log text to the editor font size.
+        ResetZoom,
+        /// Copies the selected log lines to the clipboard.
+        CopySelection,
+        /// Scrolls to the earliest error in the log.
+        ScrollToFirstError,
+        /// Scrolls to the error after the one last scrolled to, wrapping around to the first.
+        ScrollToNextError,
+        /// Shows the folder containing the log file in the system file manager.
+        RevealLogDirectory,
+        /// Selects the visible log line below the selected one.
+        SelectNextLine,
+        /// Selects the visible log line above the selected one.
+        SelectPreviousLine,
+        /// Selects the visible log line a page below the selected one.
+        SelectNextPage,
+        /// Selects the visible log line a page above the selected one.
+        SelectPreviousPage,
+        /// Selects the first visible log line.
+        SelectFirstLine,
+        /// Selects the last visible log line.
+        SelectLastLine,
+        /// Selects every visible log line.
+        SelectAllLines,
+        /// Adds the selected log lines, or all lines matching the filter, to the agent thread.
+        AddToAgentThread,
+        /// Copies the log file's path and the line number of the selected line, as `path:line`.
+        CopyFileReference,
+        /// Shows only the lines from the selected line's target, or every target again when
+        /// that's the only one shown.
+        ShowOnlySelectedTarget,
+        /// Shows the lines from every target again.
+        ShowAllTargets,
+    ]
+);
+
+const MAX_LINES_LIMIT: usize = 100_000;
+const MIN_POLL_INTERVAL_MS: u64 = 10;
+const MAX_POLL_INTERVAL_MS: u64 = 10_000;
+const MAX_CONTEXT_LINE_COUNT: usize = 100;
+const MAX_ROTATION_OVERLAP_LINE_COUNT: usize = 1_000;
+const MIN_LINE_LENGTH_LIMIT: usize = 100;
+const MAX_LINE_LENGTH_LIMIT: usize = 1_000_000;
+/// The numbers of lines around each match offered by the filter context menu.
+const FILTER_CONTEXT_PRESETS: [usize; 6] = [0, 1, 2, 3, 5, 10];
+/// The windows offered by the time range filter, in minutes.
+const TIME_WINDOW_PRESETS: [(&str, i64); 4] = [
+    ("Last 1 Minute", 1),
+    ("Last 5 Minutes", 5),
+    ("Last 15 Minutes", 15),
+    ("Last Hour", 60),
+];
+const OPEN_LOG_VIEW_NAMESPACE: &str = "open_log_view";
+const SEARCH_QUERY_KEY: &str = "search_query";
+const FONT_SCALE_KEY: &str = "font_scale";
+const COMPACT_KEY: &str = "compact";
+const FONT_SCALE_STEP: f32 = 0.1;
+const MIN_FONT_SCALE: f32 = 0.5;
+const MAX_FONT_SCALE: f32 = 3.0;
+/// The most bands the level density gutter divides the list into.
+const DENSITY_BAND_COUNT: usize = 100;
+/// The most targets offered as quick filters in the toolbar.
+const TARGET_CHIP_LIMIT: usize = 6;
+/// The most characters of a line shown in the tooltip of a truncated row.
+const LINE_TOOLTIP_MAX_CHARS: usize = 1000;
+const SEARCH_HISTORY_LIMIT: usize = 20;
+/// How long the query must stay unchanged before it's recorded in the search history.
+const SEARCH_HISTORY_DEBOUNCE: Duration = Duration::from_secs(1);
+/// How long errors are gathered before notifying about them, so a burst produces one toast.
+const ERROR_NOTIFICATION_DEBOUNCE: Duration = Duration::from_secs(2);
+/// How long the watcher waits after a change to collect further changes into the same update.
+const WATCHER_BATCH_INTERVAL: Duration = Duration::from_millis(50);
+/// How long navigating to an entry takes to ease the list there with `smooth_scroll` on.
+const SMOOTH_SCROLL_DURATION: Duration = Duration::from_millis(150);
+const SMOOTH_SCROLL_FRAME_INTERVAL: Duration = Duration::from_millis(8);
+/// The prefix the remote server gives its log messages before they're relayed to the client and
+/// written to the local log.
+const REMOTE_SERVER_LOG_MARKER: &str = "(remote server)";
+/// How many of the latest seconds the toolbar's log rate sparkline covers.
+const LOG_RATE_WINDOW_SECS: u64 = 60;
+/// How much of the end of a large log is read and shown first, while the rest of it loads.
+const TAIL_PREVIEW_BYTES: usize = 256 * 1024;
+/// How many entries the full file search checks against the query between yielding to the UI.
+const FULL_FILE_SEARCH_CHUNK_SIZE: usize = 1_000;
+/// The most bytes of log entries added to an agent thread at once, so a long log doesn't use up
+/// the model's context.
+const AGENT_CONTEXT_MAX_BYTES: usize = 32 * 1024;
+/// How long lines that arrived while the view was unfocused stay marked once it's focused again.
+const NEW_LINE_MARKER_DURATION: Duration = Duration::from_secs(5);
+
+pub fn init(cx: &mut App) {
+    workspace::register_serializable_item::<OpenLogView>(cx);
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &AddToAgentThread, window, cx| {
+            let Some(open_log) = workspace.active_item_as::<OpenLogView>(cx) else {
+                return;
+            };
+            let Some(text) = open_log.read(cx).agent_context(cx) else {
+                return;
+            };
+            if !agent_ui::AgentPanel::insert_text_into_thread(workspace, text, window, cx) {
+                struct OpenLogNoAgentThread;
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::unique::<OpenLogNoAgentThread>(),
+                        "Open an agent thread to add log lines to it",
+                    )
+                    .autohide(),
+                    cx,
+                );
+            }
+        });
+    })
+    .detach();
+}
+
+pub fn open(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    open_source(workspace, LogSource::Combined, window, cx);
+}
+
+pub fn open_old_log(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    open_source(
+        workspace,
+        LogSource::File(paths::old_log_file().clone()),
+        window,
+        cx,
+    );
+}
+
+pub fn tail_file(workspace: &mut Workspace, window: &mut Window, cx: &mut Context<Workspace>) {
+    let paths = workspace.prompt_for_open_path(
+        PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Tail".into()),
+        },
+        DirectoryLister::Local(
+            workspace.project().clone(),
+            workspace.app_state().fs.clone(),
+        ),
+        window,
+        cx,
+    );
+    cx.spawn_in(window, async move |workspace, cx| {
+        let Some(path) = paths.await.ok().flatten().and_then(|mut paths| paths.pop()) else {
+            return;
+        };
+        workspace
+            .update_in(cx, |workspace, window, cx| {
+                open_source(workspace, LogSource::File(path), window, cx)
+            })
+            .ok();
+    })
+    .detach();
+}
+
+/// Opens the log with its filter set to `query` before it's first shown, so the toolbar's filter
+/// box shows it too, and scrolls to the first match once the log is loaded. Without a query, the
+/// filter box is focused instead.
+pub fn open_filtered(
+    query: String,
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let focus_filter = query.is_empty();
+    let project = workspace.project().clone();
+    let open_log = cx.new(|cx| {
+        let mut open_log = OpenLogView::new(project, LogSource::Combined, None, window, cx);
+        if !focus_filter {
+            open_log.set_search_query(query, cx);
+            open_log.select_first_match_on_load = true;
+        }
+        open_log
+    });
+    show_toasts_in_workspace(&open_log, cx);
+    workspace.add_item_to_active_pane(Box::new(open_log.clone()), None, true, window, cx);
+    if focus_filter {
+        open_log.update(cx, |_, cx| cx.emit(OpenLogEvent::FocusFilter));
+    }
+}
+
+fn open_source(
+    workspace: &mut Workspace,
+    source: LogSource,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let open_log =
+        cx.new(|cx| OpenLogView::new(workspace.project().clone(), source, None, window, cx));
+    show_toasts_in_workspace(&open_log, cx);
+    workspace.add_item_to_active_pane(Box::new(open_log), None, true, window, cx);
+}
+
+fn show_toasts_in_workspace(open_log: &Entity<OpenLogView>, cx: &mut Context<Workspace>) {
+    cx.subscribe(open_log, |workspace, open_log, event, cx| match event {
+        OpenLogEvent::ShowToast(toast) => workspace.show_toast(toast.clone(), cx),
+        OpenLogEvent::ErrorsLogged(error_count) => {
+            struct OpenLogErrorsLogged;
+            let message = if *error_count == 1 {
+                "New error logged".to_string()
+            } else {
+                format!("{error_count} new errors logged")
+            };
+            let workspace_handle = cx.weak_entity();
+            let open_log = open_log.downgrade();
+            workspace.show_toast(
+                Toast::new(NotificationId::unique::<OpenLogErrorsLogged>(), message).on_click(
+                    "Show Log",
+                    move |window, cx| {
+                        if let Some(open_log) = open_log.upgrade() {
+                            workspace_handle
+                                .update(cx, |workspace, cx| {
+                                    workspace.activate_item(&open_log, true, true, window, cx)
+                                })
+                                .ok();
+                        }
+                    },
+                ),
+                cx,
+            );
+        }
+        OpenLogEvent::StateChanged
+        | OpenLogEvent::FocusFilter
+        | OpenLogEvent::FilterCleared
+        | OpenLogEvent::TabChanged => {}
+    })
+    .detach();
+}
+
+/// Opens `path` in an editor with the cursor on the last line whose text is `line_text`, if any.
+/// Lines that came from another file (such as `old_log_file()` in the combined view) aren't
+/// found, leaving the cursor at the top.
+fn open_raw_log_file(
+    path: PathBuf,
+    line_text: Option<SharedString>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let Some(workspace) = Workspace::for_window(window, cx) else {
+        cx.open_url(&format!("file://{}", path.display()));
+        return;
+    };
+    let open_task = workspace.update(cx, |workspace, cx| {
+        workspace.open_abs_path(
+            path,
+            OpenOptions {
+                visible: Some(OpenVisible::None),
+                ..Default::default()
+            },
+            window,
+            cx,
+        )
+    });
+    window
+        .spawn(cx, async move |cx| {
+            let item = open_task.await?;
+            let Some(line_text) = line_text else {
+                return Ok(());
+            };
+            let editor = cx
+                .update(|_, cx| item.act_as::<Editor>(cx))?
+                .context("expected editor")?;
+            editor.update_in(cx, |editor, window, cx| {
+                if let Some(row) = last_row_with_text(&editor.text(cx), &line_text) {
+                    editor.go_to_singleton_buffer_point(Point::new(row as u32, 0), window, cx);
+                }
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+}
+
+/// Returns the zero-based row of the last line in `content` that is exactly `line_text`. The last
+/// is taken as entries are usually looked up soon after being logged.
+fn last_row_with_text(content: &str, line_text: &str) -> Option<usize> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, text)| *text == line_text)
+        .map(|(row, _)| row)
+        .last()
+}
+
+fn render_session_separator(cx: &App) -> impl IntoElement {
+    let border_color = cx.theme().colors().border;
+    h_flex()
+        .w_full()
+        .py_1()
+        .px_4()
+        .gap_2()
+        .child(div().h_px().flex_1().bg(border_color))
+        .child(
+            Label::new("Current Session")
+                .size(LabelSize::XSmall)
+                .color(Color::Muted),
+        )
+        .child(div().h_px().flex_1().bg(border_color))
+}
+
+fn render_time_gap_separator(gap: TimeDelta, cx: &App) -> impl IntoElement {
+    let border_color = cx.theme().colors().border_variant;
+    h_flex()
+        .w_full()
+        .px_4()
+        .gap_2()
+        .child(div().h_px().flex_1().bg(border_color))
+        .child(
+            Label::new(format!("{} gap", format_relative_time(gap)))
+                .size(LabelSize::XSmall)
+                .color(Color::Placeholder),
+        )
+        .child(div().h_px().flex_1().bg(border_color))
+}
+
+/// Opens the file a `path:line` reference in the log resolved to, with the cursor on that line.
+fn open_file_reference(
+    resolved_path: ResolvedPath,
+    reference: &FileReference,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let Some(workspace) = Workspace::for_window(window, cx) else {
+        return;
+    };
+    let open_task = workspace.update(cx, |workspace, cx| match resolved_path {
+        ResolvedPath::ProjectPath { project_path, .. } => {
+            workspace.open_path(project_path, None, true, window, cx)
+        }
+        ResolvedPath::AbsPath { path, .. } => workspace.open_abs_path(
+            PathBuf::from(path),
+            OpenOptions {
+                visible: Some(OpenVisible::None),
+                ..Default::default()
+            },
+            window,
+            cx,
+        ),
+    });
+    let point = Point::new(
+        reference.row.saturating_sub(1),
+        reference
+            .column
+            .map_or(0, |column| column.saturating_sub(1)),
+    );
+    window
+        .spawn(cx, async move |cx| {
+            let item = open_task.await?;
+            let editor = cx
+                .update(|_, cx| item.act_as::<Editor>(cx))?
+                .context("expected editor")?;
+            editor.update_in(cx, |editor, window, cx| {
+                editor.go_to_singleton_buffer_point(point, window, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+}
+
+#[derive(Clone, Debug, RegisterSetting)]
+pub struct LogViewSettings {
+    pub max_lines: usize,
+    pub max_bytes: Option<usize>,
+    pub poll_interval: Duration,
+    pub filter_presets: Vec<LogFilterPresetContent>,
+    pub context_line_count: usize,
+    pub max_line_length: usize,
+    pub notify_on_error: bool,
+    pub record_separator: Option<RecordSeparator>,
+    pub smooth_scroll: bool,
+    pub code_block_language: String,
+    /// Colors overriding the theme's for lines at each level, indexed by `LogLevel`.
+    pub level_colors: [Option<Hsla>; LogLevel::ALL.len()],
+    pub rotation_overlap_line_count: usize,
+    /// The gap between the timestamps of adjacent entries past which a marker is shown between
+    /// them. `None` when the markers are turned off.
+    pub time_gap_threshold: Option<TimeDelta>,
+}
+
+impl Settings for LogViewSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let log_view = content.log_view.clone().unwrap();
+        Self {
+            max_lines: log_view.max_lines.unwrap().clamp(1, MAX_LINES_LIMIT),
+            max_bytes: log_view.max_bytes,
+            poll_interval: Duration::from_millis(
+                log_view
+                    .poll_interval_ms
+                    .unwrap()
+                    .clamp(MIN_POLL_INTERVAL_MS, MAX_POLL_INTERVAL_MS),
+            ),
+            filter_presets: log_view.filter_presets.unwrap(),
+            context_line_count: log_view
+                .context_line_count
+                .unwrap()
+                .clamp(1, MAX_CONTEXT_LINE_COUNT),
+            max_line_length: log_view
+                .max_line_length
+                .unwrap()
+                .clamp(MIN_LINE_LENGTH_LIMIT, MAX_LINE_LENGTH_LIMIT),
+            notify_on_error: log_view.notify_on_error.unwrap(),
+            record_separator: log_view
+                .record_separator
+                .and_then(|pattern| RecordSeparator::new(pattern).log_err()),
+            smooth_scroll: log_view.smooth_scroll.unwrap(),
+            code_block_language: log_view.code_block_language.unwrap(),
+            level_colors: parse_level_colors(log_view.level_colors.unwrap()),
+            rotation_overlap_line_count: log_view
+                .rotation_overlap_line_count
+                .unwrap()
+                .min(MAX_ROTATION_OVERLAP_LINE_COUNT),
+            time_gap_threshold: log_view
+                .time_gap_threshold_secs
+                .filter(|&seconds| seconds > 0)
+                .and_then(|seconds| TimeDelta::try_seconds(i64::try_from(seconds).ok()?)),
+        }
+    }
+}
+
+/// Parses the `level_colors` setting, logging colors that can't be parsed and keeping the theme's
+/// color for their level instead.
+fn parse_level_colors(
+    level_colors: settings::LogViewLevelColorsContent,
+) -> [Option<Hsla>; LogLevel::ALL.len()] {
+    let settings::LogViewLevelColorsContent {
+        error,
+        warn,
+        info,
+        debug,
+        trace,
+    } = level_colors;
+    [error, warn, info, debug, trace].map(|color| {
+        let color = color?;
+        theme::try_parse_color(&color)
+            .with_context(|| format!("invalid log level color {color:?}"))
+            .log_err()
+    })
+}
+
+/// The `record_separator` setting, compiled for matching both decoded text and the raw bytes
+/// appended to the log file.
+#[derive(Clone, Debug)]
+pub struct RecordSeparator {
+    pattern: String,
+    regex: Regex,
+    bytes_regex: regex::bytes::Regex,
+}
+
+impl RecordSeparator {
+    fn new(pattern: String) -> anyhow::Result<Self> {
+        let regex = Regex::new(&pattern)
+            .with_context(|| format!("invalid log view record separator {pattern:?}"))?;
+        let bytes_regex = regex::bytes::Regex::new(&pattern)?;
+        Ok(Self {
+            pattern,
+            regex,
+            bytes_regex,
+        })
+    }
+}
+
+impl PartialEq for RecordSeparator {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern == other.pattern
+    }
+}
+
+/// The file or files shown by an `OpenLogView`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogSource {
+    /// `old_log_file()` followed by `log_file()`, as if they were one file.
+    #[default]
+    Combined,
+    /// A single log file, such as `old_log_file()` or a rotated log.
+    File(PathBuf),
+}
+
+impl LogSource {
+    /// The file that's opened, truncated and appended to.
+    fn path(&self) -> &Path {
+        match self {
+            LogSource::Combined => paths::log_file(),
+            LogSource::File(path) => path,
+        }
+    }
+
+    /// The counterpart in the current logs directory of a log file from another one, as when the
+    /// view is restored after Zed restarts with a different data directory.
+    fn relocated(&self) -> Option<LogSource> {
+        let LogSource::File(path) = self else {
+            return None;
+        };
+        if path.parent() == Some(paths::logs_dir().as_path()) {
+            return None;
+        }
+        let file_name = path.file_name()?;
+        if Some(file_name) == paths::log_file().file_name() {
+            Some(LogSource::Combined)
+        } else if Some(file_name) == paths::old_log_file().file_name() {
+            Some(LogSource::File(paths::old_log_file().clone()))
+        } else {
+            None
+        }
+    }
+
+    fn tab_label(&self) -> SharedString {
+        match self {
+            LogSource::Combined => "Log".into(),
+            LogSource::File(path) if path == paths::old_log_file() => "Old Log".into(),
+            LogSource::File(path) => path.file_name().map_or_else(
+                || "Log".into(),
+                |file_name| file_name.to_string_lossy().into_owned().into(),
+            ),
+        }
+    }
+}
+
+pub struct OpenLogView {
+    focus_handle: FocusHandle,
+    project: Entity<Project>,
+    source: LogSource,
+    lines: VecDeque<LogLine>,
+    max_lines: usize,
+    /// When set, lines are also dropped from the front of `lines` while they take up more bytes.
+    max_bytes: Option<usize>,
+    /// The bytes of text in `lines`, kept up to date as lines are added and dropped.
+    byte_count: usize,
+    list_state: ListState,
+    /// The query and levels a line has to match to be shown.
+    filter: LogFilter,
+    /// Whether plain-text queries are fuzzy matched against each line, ranking lines by score.
+    fuzzy_match: bool,
+    /// Whether copied lines leave out each entry's timestamp and level.
+    copy_messages_only: bool,
+    /// The byte ranges of each line's header matched by a fuzzy query, keyed by line index.
+    fuzzy_match_ranges: HashMap<usize, Vec<Range<usize>>>,
+    /// The score of each line matched by a fuzzy query, keyed by line index.
+    fuzzy_match_scores: HashMap<usize, f64>,
+    foreground_executor: ForegroundExecutor,
+    background_executor: BackgroundExecutor,
+    /// How many of `lines` are at each level, kept up to date as lines are added and dropped.
+    level_counts: LevelCounts,
+    target_counts: TargetCounts,
+    time_window: Option<TimeWindow>,
+    /// Whether lines without a timestamp are kept while `time_window` is set.
+    keep_untimed_lines: bool,
+    filtered_indices: Vec<usize>,
+    /// The error and warning density gutter for `filtered_indices`, computed when next rendered.
+    level_density: Option<Vec<LevelDensity>>,
+    /// Whether the query selects the lines that don't match it.
+    invert_filter: bool,
+    /// Targets (the `[module::path]` token of a line) whose lines are hidden.
+    muted_targets: HashSet<String>,
+    /// When not empty, only lines from these targets are shown.
+    shown_targets: HashSet<String>,
+    /// When set, only lines logged by this host are shown. Only offered for remote projects.
+    host_filter: Option<LogHost>,
+    /// The toolbar's exclusion box, kept apart from the query so lines can be picked by one and
+    /// hidden by the other. An entry whose line contains any of its terms is hidden.
+    exclusion_query: String,
+    /// The lowercased terms of `exclusion_query`.
+    exclusion_terms: Vec<String>,
+    /// Whether lines not matching the query stay visible, with matches only highlighted.
+    show_non_matching: bool,
+    /// The number of lines before and after each match that are shown too, dimmed, like
+    /// `grep -C`. Only applies while non-matching lines are hidden.
+    filter_context_line_count: usize,
+    /// Indices into `lines` of the visible lines matching the query, in order.
+    match_line_indices: Vec<usize>,
+    /// The line selected by `SelectNextMatch`/`SelectPreviousMatch`.
+    current_match: Option<usize>,
+    /// The size and load time of the last full load of the log files.
+    load_stats: Option<LogLoadStats>,
+    log_rate: LogRate,
+    /// Redraws the log rate sparkline each second while it has lines to show, so it moves along
+    /// when none arrive.
+    _log_rate_refresh_task: Option<Task<()>>,
+    /// Byte length of the complete lines read from `log_file()` so far.
+    last_byte_offset: usize,
+    following: bool,
+    /// Lines received while paused, flushed into `lines` on resume.
+    paused_lines: VecDeque<SharedString>,
+    /// Whether the log was truncated or rotated while paused, in which case
+    /// `paused_lines` replaces `lines` rather than extending it.
+    paused_reload: bool,
+    /// The `session_start` of the reload in `paused_lines`, less any lines dropped from its front.
+    paused_session_start: Option<usize>,
+    show_line_numbers: bool,
+    /// Whether the lines around the selected line are shown below the list, ignoring the filter.
+    show_context_pane: bool,
+    /// Multiplier applied to the editor font size for the log text.
+    font_scale: f32,
+    /// Whether each entry is a single tightly packed row, to fit more lines on screen.
+    compact: bool,
+    wrap_lines: bool,
+    render_ansi: bool,
+    /// Whether paths, numbers, quoted strings and UUIDs are colored within each line.
+    highlight_tokens: bool,
+    /// Whether error and warning rows get a faint background in their level's color.
+    tint_rows_by_level: bool,
+    show_relative_time: bool,
+    /// The time relative times are measured from while frozen, so they don't tick while being
+    /// read. Moved up to the current time when new lines arrive.
+    frozen_now: Option<DateTime<FixedOffset>>,
+    /// Re-renders the view periodically so relative times stay current.
+    _relative_time_task: Option<Task<()>>,
+    /// Errors logged while following that haven't been notified about yet.
+    unnotified_error_count: usize,
+    _error_notification_task: Option<Task<()>>,
+    /// Lines expanded to full height while `wrap_lines` is off.
+    expanded_lines: HashSet<usize>,
+    /// Lines whose continuation lines are hidden behind a disclosure.
+    collapsed_lines: HashSet<usize>,
+    /// Lines bookmarked by the user, cycled through from the toolbar.
+    pinned_lines: HashSet<usize>,
+    /// Lines whose trailing JSON payload is shown pretty-printed.
+    json_expanded_lines: HashSet<usize>,
+    /// Lines longer than the `max_line_length` setting that are shown in full anyway.
+    full_length_lines: HashSet<usize>,
+    /// The line a selection was started from with a click, extended by shift-clicking.
+    selection_anchor: Option<usize>,
+    /// The line at the other end of the selection from `selection_anchor`.
+    selection_head: Option<usize>,
+    /// The most recently logged error, shown above the list while scrolled away from the tail.
+    latest_error_line: Option<usize>,
+    /// The error line last scrolled to with `ScrollToFirstError` or `ScrollToNextError`.
+    current_error_line: Option<usize>,
+    /// Whether the last line of the list is in the viewport.
+    viewing_tail: bool,
+    /// How many lines were appended since the last line of the list was last in the viewport.
+    unseen_line_count: usize,
+    /// How many lines were dropped from the front of `lines` to stay within `max_lines` since the
+    /// log was last loaded.
+    trimmed_line_count: usize,
+    /// Whether the query is also run over the entries trimmed from `lines`, by scanning the log
+    /// files on disk.
+    search_full_file: bool,
+    /// Trimmed entries that match the query, oldest first.
+    older_matches: Vec<OlderMatch>,
+    /// Scans the log files for `older_matches`. Replaced, cancelling it, when the query changes.
+    full_file_search_task: Option<Task<()>>,
+    /// The position of the current session's first entry among every entry loaded since the log
+    /// was last loaded, including trimmed ones. Only set when `old_log_file()` was loaded first.
+    session_start_entry: Option<usize>,
+    /// How often the watcher checks the log files for changes.
+    poll_interval: Duration,
+    /// When set, the log is split into records with this rather than into lines.
+    record_separator: Option<RecordSeparator>,
+    _watch_task: Task<()>,
+    /// Set when `_watch_task` stopped without the view being dropped, so no new lines will arrive.
+    watcher_disconnected: bool,
+    /// Set until the log files have been read for the first time, which can take a while for
+    /// large logs, so an empty view isn't mistaken for an empty log.
+    loading: bool,
+    /// Set while the viewed file doesn't exist, until the watcher sees it created.
+    waiting_for_file: bool,
+    /// Whether the banner offering to switch to the current logs directory was dismissed.
+    relocation_dismissed: bool,
+    /// Set when the view was opened filtered, to scroll to the first match once the log loads.
+    select_first_match_on_load: bool,
+    /// The files that `path:line` references in the log resolved to, keyed by the referenced
+    /// path. `None` while an absolute path is being resolved, or when it couldn't be.
+    resolved_paths: HashMap<String, Option<ResolvedPath>>,
+    _settings_subscription: Subscription,
+    _project_subscription: Subscription,
+    /// The entry holding the search bar's active match, highlighted like the filter's.
+    search_bar_active_line: Option<usize>,
+    _search_bar_subscription: Subscription,
+    /// How many lines there were when the view last lost focus. Lines past these arrived unseen,
+    /// and are marked when focus returns.
+    last_viewed_line_count: Option<usize>,
+    /// The first line that arrived while the view was unfocused, from which lines are marked with
+    /// an accent bar until `NEW_LINE_MARKER_DURATION` after it's focused again.
+    new_lines_start: Option<usize>,
+    _new_lines_marker_task: Option<Task<()>>,
+    _focus_subscriptions: [Subscription; 2],
+    /// What the tab showed when it was last updated.
+    tab_status: TabStatus,
+    _tab_status_subscription: Subscription,
+    /// Eases the list towards the entry last navigated to. Replaced, stopping it, by the next jump.
+    _scroll_animation: Option<Task<()>>,
+}
+
+impl OpenLogView {
+    pub fn new(
+        project: Entity<Project>,
+        source: LogSource,
+        serialized: Option<SerializedOpenLogView>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let fs = <dyn Fs>::global(cx);
+        let focus_handle = cx.focus_handle();
+        let focus_subscriptions = [
+            cx.on_focus_in(&focus_handle, window, |this, _window, cx| {
+                this.mark_new_lines(cx)
+            }),
+            cx.on_focus_out(&focus_handle, window, |this, _, _window, _cx| {
+                this.last_viewed_line_count = Some(this.lines.len());
+            }),
+        ];
+
+        // The tab only redraws on `ItemEvent::UpdateTab`, so it's sent whenever a change to the
+        // view alters what the tab shows, rather than from everywhere that can.
+        let tab_status_subscription = cx.observe_self(|this, cx| {
+            let tab_status = this.tab_status();
+            if tab_status != this.tab_status {
+                this.tab_status = tab_status;
+                cx.emit(OpenLogEvent::TabChanged);
+            }
+        });
+
+        let list_state = ListState::new(0, ListAlignment::Bottom, px(2048.));
+        // Appended lines only scroll the list while it's at the tail, so reading further up
+        // isn't interrupted. Scrolling back to the end resumes following.
+        list_state.set_follow_mode(FollowMode::Tail);
+        let this = cx.weak_entity();
+        list_state.set_scroll_handler(move |event, _window, cx| {
+            let viewing_tail = event.visible_range.end >= event.count;
+            let this = this.clone();
+            // The handler runs while the list state is borrowed, so update the view afterwards.
+            cx.defer(move |cx| {
+                this.update(cx, |this, cx| {
+                    if this.viewing_tail != viewing_tail {
+                        this.viewing_tail = viewing_tail;
+                        if viewing_tail {
+                            this.unseen_line_count = 0;
+                        }
+                        cx.notify();
+                    }
+                })
+                .ok();
+            });
+        });
+
+        let poll_interval = LogViewSettings::get_global(cx).poll_interval;
+        let watch_task = Self::watch_log_file(fs, source.clone(), poll_interval, cx);
+
+        let serialized = serialized.unwrap_or_else(|| SerializedOpenLogView {
+            search_query: KeyValueStore::global(cx)
+                .scoped(OPEN_LOG_VIEW_NAMESPACE)
+                .read(SEARCH_QUERY_KEY)
+                .log_err()
+                .flatten()
+                .unwrap_or_default(),
+            level_filter: LevelMask::ALL,
+            following: true,
+            source: source.clone(),
+            fuzzy_match: false,
+            whole_word: false,
+            invert_filter: false,
+            show_non_matching: false,
+            muted_targets: Vec::new(),
+            shown_targets: Vec::new(),
+        });
+
+        let font_scale = KeyValueStore::global(cx)
+            .scoped(OPEN_LOG_VIEW_NAMESPACE)
+            .read(FONT_SCALE_KEY)
+            .log_err()
+            .flatten()
+            .and_then(|font_scale| font_scale.parse::<f32>().log_err())
+            .map_or(1.0, |font_scale| {
+                font_scale.clamp(MIN_FONT_SCALE, MAX_FONT_SCALE)
+            });
+        let compact = KeyValueStore::global(cx)
+            .scoped(OPEN_LOG_VIEW_NAMESPACE)
+            .read(COMPACT_KEY)
+            .log_err()
+            .flatten()
+            .is_some_and(|compact| compact == "true");
+
+        // Paths that didn't resolve may do so in a newly added worktree, and vice versa.
+        let project_subscription = cx.subscribe(&project, |this, _, event, cx| {
+            if matches!(
+                event,
+                project::Event::WorktreeAdded(_) | project::Event::WorktreeRemoved(_)
+            ) {
+                this.resolved_paths.clear();
+                cx.notify();
+            }
+        });
+
+        // The search bar searches the shown entries, so the filter changing invalidates its matches.
+        let search_bar_subscription = cx.subscribe_self(|_, event: &OpenLogEvent, cx| {
+            if matches!(event, OpenLogEvent::StateChanged) {
+                cx.emit(SearchEvent::MatchesInvalidated);
+            }
+        });
+
+        let settings = LogViewSettings::get_global(cx);
+        let (max_lines, max_bytes) = (settings.max_lines, settings.max_bytes);
+        let settings_subscription = cx.observe_global::<SettingsStore>(|this, cx| {
+            let LogViewSettings {
+                max_lines,
+                max_bytes,
+                poll_interval,
+                record_separator,
+                ..
+            } = LogViewSettings::get_global(cx).clone();
+            // Level colors and other settings read while rendering may have changed.
+            cx.notify();
+            if poll_interval != this.poll_interval || record_separator != this.record_separator {
+                this.poll_interval = poll_interval;
+                this.record_separator = record_separator;
+                this.reload(cx);
+            }
+            if max_lines != this.max_lines || max_bytes != this.max_bytes {
+                this.max_lines = max_lines;
+                this.max_bytes = max_bytes;
+                let mut dropped_line_count = 0;
+                while this.lines.len() > max_lines
+                    || (this.lines.len() > 1 && this.exceeds_byte_budget(0))
+                {
+                    this.drop_front_line();
+                    dropped_line_count += 1;
+                }
+                if dropped_line_count > 0 {
+                    this.shift_line_indices(dropped_line_count);
+                    this.update_filtered_indices(this.lines.len(), dropped_line_count);
+                    this.record_trimmed_lines(dropped_line_count);
+                    cx.notify();
+                }
+            }
+        });
+
+        let mut this = Self {
+            focus_handle,
+            project,
+            source,
+            lines: VecDeque::with_capacity(max_lines),
+            max_lines,
+            max_bytes,
+            byte_count: 0,
+            list_state,
+            filter: LogFilter::new(serialized.whole_word, serialized.level_filter),
+            fuzzy_match: serialized.fuzzy_match,
+            copy_messages_only: false,
+            fuzzy_match_ranges: HashMap::default(),
+            fuzzy_match_scores: HashMap::default(),
+            foreground_executor: cx.foreground_executor().clone(),
+            background_executor: cx.background_executor().clone(),
+            level_counts: LevelCounts::default(),
+            target_counts: TargetCounts::default(),
+            time_window: None,
+            keep_untimed_lines: true,
+            filtered_indices: Vec::new(),
+            level_density: None,
+            invert_filter: serialized.invert_filter,
+            muted_targets: serialized.muted_targets.into_iter().collect(),
+            shown_targets: serialized.shown_targets.into_iter().collect(),
+            host_filter: None,
+            exclusion_query: String::new(),
+            exclusion_terms: Vec::new(),
+            show_non_matching: serialized.show_non_matching,
+            filter_context_line_count: 0,
+            match_line_indices: Vec::new(),
+            current_match: None,
+            load_stats: None,
+            log_rate: LogRate::new(),
+            _log_rate_refresh_task: None,
+            last_byte_offset: 0,
+            following: serialized.following,
+            paused_lines: VecDeque::new(),
+            paused_reload: false,
+            paused_session_start: None,
+            show_line_numbers: true,
+            show_context_pane: false,
+            font_scale,
+            compact,
+            wrap_lines: false,
+            render_ansi: true,
+            highlight_tokens: true,
+            tint_rows_by_level: true,
+            show_relative_time: false,
+            frozen_now: None,
+            _relative_time_task: None,
+            unnotified_error_count: 0,
+            _error_notification_task: None,
+            expanded_lines: HashSet::default(),
+            collapsed_lines: HashSet::default(),
+            pinned_lines: HashSet::default(),
+            json_expanded_lines: HashSet::default(),
+            full_length_lines: HashSet::default(),
+            selection_anchor: None,
+            selection_head: None,
+            latest_error_line: None,
+            current_error_line: None,
+            viewing_tail: true,
+            unseen_line_count: 0,
+            trimmed_line_count: 0,
+            search_full_file: false,
+            older_matches: Vec::new(),
+            full_file_search_task: None,
+            session_start_entry: None,
+            poll_interval,
+            record_separator: LogViewSettings::get_global(cx).record_separator.clone(),
+            _watch_task: watch_task,
+            watcher_disconnected: false,
+            loading: true,
+            waiting_for_file: false,
+            relocation_dismissed: false,
+            select_first_match_on_load: false,
+            resolved_paths: HashMap::default(),
+            _settings_subscription: settings_subscription,
+            _project_subscription: project_subscription,
+            search_bar_active_line: None,
+            _search_bar_subscription: search_bar_subscription,
+            last_viewed_line_count: None,
+            new_lines_start: None,
+            _new_lines_marker_task: None,
+            _focus_subscriptions: focus_subscriptions,
+            tab_status: TabStatus::default(),
+            _tab_status_subscription: tab_status_subscription,
+            _scroll_animation: None,
+        };
+        this.apply_search_query(serialized.search_query);
+        this
+    }
+
+    /// Loads `old_log_file()` and `log_file()`, then appends lines as `log_file()` grows. When
+    /// the file is truncated or rotated (replaced by a new file), both are loaded again and the
+    /// new file is watched instead. `old_log_file()` is watched too, and any change to it (such as
+    /// a final flush after rotation) also reloads both, so its lines always precede the new ones.
+    /// A `LogSource::File` is loaded and watched the same way, without a file preceding it. If it
+    /// doesn't exist, as for a log the program writing it hasn't started yet, it's waited for.
+    fn watch_log_file(
+        fs: Arc<dyn Fs>,
+        source: LogSource,
+        poll_interval: Duration,
+        cx: &mut Context<Self>,
+    ) -> Task<()> {
+        cx.spawn(async move |this, cx| {
+            let log_file_path = source.path();
+            let old_log_file_path = match &source {
+                LogSource::Combined => Some(paths::old_log_file().as_path()),
+                LogSource::File(_) => None,
+            };
+            let mut is_reload = false;
+            loop {
+                if old_log_file_path.is_none() && !fs.is_file(log_file_path).await {
+                    let result = this.update(cx, |this, cx| {
+                        this.loading = false;
+                        this.waiting_for_file = true;
+                        cx.notify();
+                    });
+                    if result.is_err() {
+                        return;
+                    }
+                    wait_for_file(fs.as_ref(), log_file_path, poll_interval).await;
+                    let result = this.update(cx, |this, cx| {
+                        this.waiting_for_file = false;
+                        cx.notify();
+                    });
+                    if result.is_err() {
+                        return;
+                    }
+                }
+                // A large log takes a while to read and split in full, so the entries at its end
+                // are shown first. The full load replaces them, and the list's bottom alignment
+                // keeps them in place. Records can't be cut apart reliably, so they're skipped.
+                if !is_reload
+                    && let Ok(None) = this.read_with(cx, |this, _| this.record_separator.clone())
+                    && let Some(preview) = read_tail_preview(log_file_path, TAIL_PREVIEW_BYTES)
+                        .await
+                        .log_err()
+                        .flatten()
+                {
+                    let result = this.update(cx, |this, cx| {
+                        this.set_lines(split_log_content(&preview, None), None, cx);
+                    });
+                    if result.is_err() {
+                        return;
+                    }
+                }
+                let load_start = Instant::now();
+                // Read bytes rather than strings so invalid UTF-8 (such as binary noise from a
+                // crash) is replaced instead of failing the whole load.
+                let (old_log_result, new_log_result) = futures::join!(
+                    async {
+                        match old_log_file_path {
+                            Some(old_log_file_path) => fs.load_bytes(old_log_file_path).await,
+                            None => Err(anyhow::anyhow!("no file precedes the log")),
+                        }
+                    },
+                    fs.load_bytes(log_file_path)
+                );
+                let load_time = load_start.elapsed();
+                let old_log_len = old_log_result
+                    .as_ref()
+                    .ok()
+                    .map(|old_log| old_log.len() as u64);
+                let inode = fs
+                    .metadata(log_file_path)
+                    .await
+                    .ok()
+                    .flatten()
+                    .map(|metadata| metadata.inode);
+
+                let loaded = this.update(cx, |this, cx| match (old_log_result, new_log_result) {
+                    (Err(_), Err(err)) => {
+                        this.loading = false;
+                        this.show_read_error_toast(&err, cx);
+                        false
+                    }
+                    (old_log, new_log) => {
+                        let old_log = old_log.unwrap_or_default();
+                        let new_log = new_log.unwrap_or_default();
+                        this.load_stats = Some(LogLoadStats {
+                            byte_len: (old_log.len() + new_log.len()) as u64,
+                            load_time,
+                        });
+                        let record_separator = this.record_separator.clone();
+                        let record_separator = record_separator.as_ref();
+                        let complete_len = complete_content_len(&new_log, record_separator);
+                        let old_log = String::from_utf8_lossy(&old_log);
+                        let new_log = String::from_utf8_lossy(&new_log[..complete_len]);
+                        let old_lines =
+                            split_log_content(&old_log, record_separator).collect::<Vec<_>>();
+                        let new_lines =
+                            split_log_content(&new_log, record_separator).collect::<Vec<_>>();
+                        // Lines flushed to the old file just before rotation can be written to
+                        // the new one again, so they'd otherwise show up twice.
+                        let overlap_len = rotation_overlap_len(
+                            &old_lines,
+                            &new_lines,
+                            LogViewSettings::get_global(cx).rotation_overlap_line_count,
+                        );
+                        let session_start = old_log_file_path.map(|_| old_lines.len());
+                        let lines = old_lines
+                            .into_iter()
+                            .chain(new_lines.into_iter().skip(overlap_len));
+                        if is_reload {
+                            this.receive_reload(lines, session_start, cx);
+                        } else {
+                            this.set_lines(lines, session_start, cx);
+                            if std::mem::take(&mut this.select_first_match_on_load)
+                                && let Some(&first_match) = this.match_line_indices.first()
+                            {
+                                this.select_match(first_match, cx);
+                            }
+                        }
+                        this.last_byte_offset = complete_len;
+                        this.loading = false;
+                        true
+                    }
+                });
+                match loaded {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        this.update(cx, |this, cx| this.set_watcher_disconnected(cx))
+                            .ok();
+                        return;
+                    }
+                    Err(_) => return,
+                }
+
+                let (events, _watcher) = fs.watch(log_file_path, poll_interval).await;
+                let (old_log_events, _old_log_watcher) = match old_log_file_path {
+                    Some(old_log_file_path) => {
+                        let (events, watcher) = fs.watch(old_log_file_path, poll_interval).await;
+                        (events, Some(watcher))
+                    }
+                    None => (futures::stream::pending().boxed(), None),
+                };
+                let mut events =
+                    futures::stream::select(events.map(|_| false), old_log_events.map(|_| true));
+                // The watchers only report changes made after they started, so anything written
+                // while the files were being loaded is checked for before waiting on them.
+                let mut reconciled = false;
+                let should_reload = loop {
+                    let (old_log_changed, log_changed) = if reconciled {
+                        let Some(is_old_log_event) = events.next().await else {
+                            break false;
+                        };
+                        // During a burst of logging, events arrive much faster than it's worth
+                        // redrawing. Handle everything that arrives within the interval as one
+                        // change, so the file is read and the view updated once.
+                        cx.background_executor().timer(WATCHER_BATCH_INTERVAL).await;
+                        let mut old_log_changed = is_old_log_event;
+                        let mut log_changed = !is_old_log_event;
+                        while let Some(Some(is_old_log_event)) = events.next().now_or_never() {
+                            old_log_changed |= is_old_log_event;
+                            log_changed |= !is_old_log_event;
+                        }
+                        (old_log_changed, log_changed)
+                    } else {
+                        reconciled = true;
+                        (true, true)
+                    };
+
+                    if let Some(old_log_file_path) = old_log_file_path
+                        && old_log_changed
+                    {
+                        let current_old_log_len = fs
+                            .metadata(old_log_file_path)
+                            .await
+                            .ok()
+                            .flatten()
+                            .map(|metadata| metadata.len);
+                        if current_old_log_len != old_log_len {
+                            break true;
+                        }
+                    }
+                    if !log_changed {
+                        continue;
+                    }
+                    let Ok(Some(metadata)) = fs.metadata(log_file_path).await else {
+                        continue;
+                    };
+                    let Ok((last_byte_offset, record_separator)) = this.read_with(cx, |this, _| {
+                        (this.last_byte_offset, this.record_separator.clone())
+                    }) else {
+                        return;
+                    };
+                    if Some(metadata.inode) != inode || (metadata.len as usize) < last_byte_offset {
+                        break true;
+                    }
+                    if metadata.len as usize == last_byte_offset {
+                        continue;
+                    }
+
+                    let Some((appended, complete_len)) = read_appended_content(
+                        log_file_path,
+                        last_byte_offset,
+                        record_separator.as_ref(),
+                    )
+                    .await
+                    .log_err() else {
+                        continue;
+                    };
+                    let result = this.update(cx, |this, cx| {
+                        this.receive_lines(
+                            split_log_content(&appended, record_separator.as_ref()),
+                            cx,
+                        );
+                        this.last_byte_offset = last_byte_offset + complete_len;
+                    });
+                    if result.is_err() {
+                        return;
+                    }
+                };
+                if !should_reload {
+                    this.update(cx, |this, cx| this.set_watcher_disconnected(cx))
+                        .ok();
+                    return;
+                }
+                is_reload = true;
+            }
+        })
+    }
+
+    /// Marks the lines that arrived since the view last lost focus, for a few seconds.
+    fn mark_new_lines(&mut self, cx: &mut Context<Self>) {
+        self.new_lines_start = self
+            .last_viewed_line_count
+            .take()
+            .filter(|&line_count| line_count < self.lines.len());
+        self._new_lines_marker_task = self.new_lines_start.map(|_| {
+            cx.spawn(async move |this, cx| {
+                cx.background_executor()
+                    .timer(NEW_LINE_MARKER_DURATION)
+                    .await;
+                this.update(cx, |this, cx| {
+                    this.new_lines_start = None;
+                    cx.notify();
+                })
+                .ok();
+            })
+        });
+        cx.notify();
+    }
+
+    fn set_watcher_disconnected(&mut self, cx: &mut Context<Self>) {
+        self.watcher_disconnected = true;
+        cx.notify();
+    }
+
+    /// Restarts the watcher, which loads `old_log_file()` and `log_file()` again from scratch.
+    pub fn reload(&mut self, cx: &mut Context<Self>) {
+        self.watcher_disconnected = false;
+        self._watch_task = Self::watch_log_file(
+            <dyn Fs>::global(cx),
+            self.source.clone(),
+            self.poll_interval,
+            cx,
+        );
+        cx.notify();
+    }
+
+    /// Points the view at `source`, loading it from scratch.
+    fn set_source(&mut self, source: LogSource, cx: &mut Context<Self>) {
+        self.source = source;
+        self.reload(cx);
+        cx.emit(OpenLogEvent::StateChanged);
+    }
+
+    /// Appends lines that weren't read from the log file, such as diagnostics from elsewhere in
+    /// Zed, as if they had just been logged. Each item is one line, or one record when a record
+    /// separator is set. They go through the same path as lines from the watcher, so `max_lines`,
+    /// pausing and the filter apply to them alike.
+    ///
+    /// Lines appear in the order they're received: after everything the watcher has delivered
+    /// so far and before anything it delivers later. They aren't written to disk, so they're
+    /// dropped whenever the log is loaded again, as when it's reloaded, truncated or rotated.
+    pub fn append_external_lines<'a>(
+        &mut self,
+        lines: impl IntoIterator<Item = &'a str>,
+        cx: &mut Context<Self>,
+    ) {
+        self.receive_lines(lines.into_iter(), cx);
+    }
+
+    fn receive_lines<'a>(&mut self, lines: impl Iterator<Item = &'a str>, cx: &mut Context<Self>) {
+        // Paused lines are appended after the current ones, so this is where they'll be too.
+        let first_entry = self.trimmed_line_count + self.lines.len();
+        let mut line_count = 0;
+        let lines = lines.inspect(|_| line_count += 1);
+        if self.following {
+            let error_count = self.append_lines(lines, None, cx);
+            self.notify_errors_logged(error_count, cx);
+        } else {
+            self.buffer_paused_lines(lines);
+            cx.notify();
+        }
+        self.record_log_rate(line_count, first_entry, cx);
+    }
+
+    fn record_log_rate(&mut self, line_count: usize, first_entry: usize, cx: &mut Context<Self>) {
+        if line_count == 0 {
+            return;
+        }
+        let now_second = self.log_rate.now_second();
+        // The refresh task runs until the sparkline is empty, so it only needs starting then.
+        let refreshing = self.log_rate.has_lines(now_second);
+        self.log_rate.record(now_second, line_count, first_entry);
+        if refreshing {
+            return;
+        }
+        self._log_rate_refresh_task = Some(cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(Duration::from_secs(1)).await;
+                let has_lines = this.update(cx, |this, cx| {
+                    cx.notify();
+                    this.log_rate.has_lines(this.log_rate.now_second())
+                });
+                if !matches!(has_lines, Ok(true)) {
+                    return;
+                }
+            }
+        }));
+    }
+
+    /// Scrolls to the first entry received in the busiest second the log rate sparkline shows.
+    fn scroll_to_busiest_second(&mut self, cx: &mut Context<Self>) {
+        let Some(first_entry) = self
+            .log_rate
+            .busiest_first_entry(self.log_rate.now_second())
+        else {
+            return;
+        };
+        // Entries trimmed since then are gone, so the oldest remaining one is the closest.
+        let line_index = first_entry.saturating_sub(self.trimmed_line_count);
+        let filtered_index = self
+            .filtered_indices
+            .partition_point(|&filtered_line_index| filtered_line_index < line_index);
+        if filtered_index < self.filtered_indices.len() {
+            self.set_following(false, cx);
+            self.scroll_to_entry(filtered_index, cx);
+            cx.notify();
+        }
+    }
+
+    fn receive_reload<'a>(
+        &mut self,
+        lines: impl Iterator<Item = &'a str>,
+        session_start: Option<usize>,
+        cx: &mut Context<Self>,
+    ) {
+        if self.following {
+            self.set_lines(lines, session_start, cx);
+        } else {
+            self.paused_lines.clear();
+            self.paused_reload = true;
+            self.paused_session_start = session_start;
+            self.buffer_paused_lines(lines);
+            cx.notify();
+        }
+    }
+
+    fn buffer_paused_lines<'a>(&mut self, lines: impl Iterator<Item = &'a str>) {
+        for line in lines {
+            while self.paused_lines.len() >= self.max_lines {
+                self.paused_lines.pop_front();
+                self.paused_session_start = self
+                    .paused_session_start
+                    .map(|session_start| session_start.saturating_sub(1));
+            }
+            self.paused_lines
+                .push_back(SharedString::from(line.to_string()));
+        }
+    }
+
+    pub fn load_stats(&self) -> Option<LogLoadStats> {
+        self.load_stats
+    }
+
+    pub fn is_following(&self) -> bool {
+        self.following
+    }
+
+    pub fn paused_line_count(&self) -> usize {
+        self.paused_lines.len()
+    }
+
+    pub fn set_following(&mut self, following: bool, cx: &mut Context<Self>) {
+        if self.following == following {
+            return;
+        }
+        self.following = following;
+
+        if following {
+            let paused_lines = std::mem::take(&mut self.paused_lines);
+            let lines = paused_lines.iter().map(|line| line.as_ref());
+            if std::mem::take(&mut self.paused_reload) {
+                let session_start = self.paused_session_start.take();
+                self.set_lines(lines, session_start, cx);
+            } else if !paused_lines.is_empty() {
+                self.append_lines(lines, None, cx);
+            }
+        }
+        cx.emit(OpenLogEvent::StateChanged);
+        cx.notify();
+    }
+
+    /// Replaces every line with `lines`. `session_start` is the number of those lines that came
+    /// from `old_log_file()`, if any did, so the current session's lines can be told apart.
+    fn set_lines<'a>(
+        &mut self,
+        lines: impl Iterator<Item = &'a str>,
+        session_start: Option<usize>,
+        cx: &mut Context<Self>,
+    ) {
+        self.lines.clear();
+        self.byte_count = 0;
+        self.level_counts = LevelCounts::default();
+        self.target_counts = TargetCounts::default();
+        self.filtered_indices.clear();
+        self.match_line_indices.clear();
+        self.list_state.reset(0);
+        self.expanded_lines.clear();
+        self.collapsed_lines.clear();
+        self.pinned_lines.clear();
+        self.json_expanded_lines.clear();
+        self.full_length_lines.clear();
+        self.selection_anchor = None;
+        self.selection_head = None;
+        self.latest_error_line = None;
+        self.current_error_line = None;
+        self.search_bar_active_line = None;
+        self.trimmed_line_count = 0;
+        self.session_start_entry = None;
+        self.append_lines(lines, session_start, cx);
+        self.unseen_line_count = 0;
+        self.refresh_full_file_search(cx);
+    }
+
+    /// Gathers errors that were just logged, emitting `OpenLogEvent::ErrorsLogged` for all of
+    /// them once `ERROR_NOTIFICATION_DEBOUNCE` has passed since the first.
+    fn notify_errors_logged(&mut self, error_count: usize, cx: &mut Context<Self>) {
+        if error_count == 0 || !LogViewSettings::get_global(cx).notify_on_error {
+            return;
+        }
+        self.unnotified_error_count += error_count;
+        if self._error_notification_task.is_some() {
+            return;
+        }
+        self._error_notification_task = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(ERROR_NOTIFICATION_DEBOUNCE)
+                .await;
+            this.update(cx, |this, cx| {
+                this._error_notification_task = None;
+                let error_count = std::mem::take(&mut this.unnotified_error_count);
+                cx.emit(OpenLogEvent::ErrorsLogged(error_count));
+            })
+            .ok();
+        }));
+    }
+
+    /// Appends `lines` to the log, returning how many of them are errors. The entry starting at
+    /// the `session_start`th of them is recorded as the first of the current session.
+    fn append_lines<'a>(
+        &mut self,
+        lines: impl Iterator<Item = &'a str>,
+        session_start: Option<usize>,
+        cx: &mut Context<Self>,
+    ) -> usize {
+        // Counted from the first line before any were dropped, so it stays valid while
+        // `dropped_line_count` grows.
+        let mut first_changed_line_index = self.lines.len();
+        let mut dropped_line_count = 0;
+        let mut error_count = 0;
+        let mut received_lines = false;
+        for (line_ix, line) in lines.enumerate() {
+            received_lines = true;
+            if session_start == Some(line_ix) {
+                self.session_start_entry =
+                    Some(self.trimmed_line_count + dropped_line_count + self.lines.len());
+            }
+            if self.record_separator.is_none()
+                && is_continuation_line(line)
+                && let Some(last_line) = self.lines.back_mut()
+            {
+                let previous_byte_len = last_line.byte_len();
+                last_line.push_continuation_line(line);
+                self.byte_count += last_line.byte_len() - previous_byte_len;
+                first_changed_line_index =
+                    first_changed_line_index.min(self.lines.len() - 1 + dropped_line_count);
+                // The entry being extended is kept even when it alone is over the budget.
+                while self.lines.len() > 1 && self.exceeds_byte_budget(0) {
+                    self.drop_front_line();
+                    dropped_line_count += 1;
+                }
+                continue;
+            }
+            let line = if self.record_separator.is_some() {
+                LogLine::from_record(line)
+            } else {
+                LogLine::new(line)
+            };
+            let byte_len = line.byte_len();
+            while self.lines.len() >= self.max_lines
+                || (!self.lines.is_empty() && self.exceeds_byte_budget(byte_len))
+            {
+                self.drop_front_line();
+                dropped_line_count += 1;
+            }
+            self.byte_count += byte_len;
+            self.level_counts.add(&line);
+            self.target_counts.add(&line);
+            if parse_log_level(&strip_ansi(&line.text)) == Some(LogLevel::Error) {
+                self.latest_error_line = Some(self.lines.len() + dropped_line_count);
+                error_count += 1;
+            }
+            if !self.viewing_tail {
+                self.unseen_line_count += 1;
+            }
+            self.lines.push_back(line);
+        }
+
+        self.shift_line_indices(dropped_line_count);
+        self.update_filtered_indices(
+            first_changed_line_index.saturating_sub(dropped_line_count),
+            dropped_line_count,
+        );
+        self.record_trimmed_lines(dropped_line_count);
+        if received_lines && self.frozen_now.is_some() {
+            self.frozen_now = Some(Local::now().fixed_offset());
+        }
+        cx.emit(SearchEvent::MatchesInvalidated);
+        cx.notify();
+        error_count
+    }
+
+    /// Pops the first of `lines`, keeping the state derived from it up to date. Callers are left
+    /// to shift the indices of the remaining lines.
+    fn drop_front_line(&mut self) {
+        if let Some(dropped_line) = self.lines.pop_front() {
+            self.byte_count -= dropped_line.byte_len();
+            self.level_counts.remove(&dropped_line);
+            self.target_counts.remove(&dropped_line);
+            self.record_older_match(dropped_line);
+        }
+    }
+
+    fn exceeds_byte_budget(&self, additional_bytes: usize) -> bool {
+        self.max_bytes
+            .is_some_and(|max_bytes| self.byte_count + additional_bytes > max_bytes)
+    }
+
+    /// The index in `lines` of the current session's first entry, while entries from the previous
+    /// session precede it.
+    fn session_start_line(&self) -> Option<usize> {
+        self.session_start_entry?
+            .checked_sub(self.trimmed_line_count)
+            .filter(|&line_index| line_index > 0)
+    }
+
+    /// Adds an entry that was just trimmed to `older_matches` if it matches, so they stay complete
+    /// as the log grows. While a full file search is running it will find the entry itself.
+    fn record_older_match(&mut self, line: LogLine) {
+        if self.search_full_file
+            && self.full_file_search_task.is_none()
+            && !self.filter.query.is_empty()
+            && !self.is_fuzzy_query()
+            && self.line_passes_filters(&line)
+            && self.line_matches_query(&line)
+        {
+            self.older_matches.push(OlderMatch {
+                in_old_log: false,
+                line,
+            });
+        }
+    }
+
+    pub fn is_searching_full_file(&self) -> bool {
+        self.search_full_file
+    }
+
+    pub fn toggle_search_full_file(&mut self, cx: &mut Context<Self>) {
+        self.search_full_file = !self.search_full_file;
+        self.refresh_full_file_search(cx);
+        cx.notify();
+    }
+
+    pub fn cancel_full_file_search(&mut self, cx: &mut Context<Self>) {
+        self.full_file_search_task = None;
+        cx.notify();
+    }
+
+    /// Restarts the search of the entries trimmed from `lines`, reading them back from the log
+    /// files and adding those that match the query to `older_matches` a chunk at a time. Fuzzy
+    /// queries aren't searched, as their matches are ranked rather than kept in log order.
+    fn refresh_full_file_search(&mut self, cx: &mut Context<Self>) {
+        self.older_matches.clear();
+        self.full_file_search_task = None;
+        if !self.search_full_file
+            || self.filter.query.is_empty()
+            || self.is_fuzzy_query()
+            || self.trimmed_line_count == 0
+        {
+            return;
+        }
+
+        let fs = <dyn Fs>::global(cx);
+        let trimmed_line_count = self.trimmed_line_count;
+        let log_file_path = self.source.path().to_path_buf();
+        let old_log_file_path = match &self.source {
+            LogSource::Combined => Some(paths::old_log_file().as_path()),
+            LogSource::File(_) => None,
+        };
+        let record_separator = self.record_separator.clone();
+        self.full_file_search_task = Some(cx.spawn(async move |this, cx| {
+            let old_log = match old_log_file_path {
+                Some(old_log_file_path) => fs.load_bytes(old_log_file_path).await.ok(),
+                None => None,
+            };
+            let new_log = fs.load_bytes(&log_file_path).await.ok();
+            let trimmed_entries = cx
+                .background_spawn(async move {
+                    let record_separator = record_separator.as_ref();
+                    let old_log = String::from_utf8_lossy(old_log.as_deref().unwrap_or_default());
+                    let new_log = String::from_utf8_lossy(new_log.as_deref().unwrap_or_default());
+                    let old_entries = parse_log_entries(
+                        split_log_content(&old_log, record_separator),
+                        record_separator.is_some(),
+                        trimmed_line_count,
+                    );
+                    let new_entries = parse_log_entries(
+                        split_log_content(&new_log, record_separator),
+                        record_separator.is_some(),
+                        trimmed_line_count - old_entries.len(),
+                    );
+                    (old_entries, new_entries)
+                })
+                .await;
+
+            let (old_entries, new_entries) = trimmed_entries;
+            let mut entries = old_entries
+                .into_iter()
+                .map(|line| (true, line))
+                .chain(new_entries.into_iter().map(|line| (false, line)));
+            loop {
+                let chunk = entries
+                    .by_ref()
+                    .take(FULL_FILE_SEARCH_CHUNK_SIZE)
+                    .collect::<Vec<_>>();
+                if chunk.is_empty() {
+                    break;
+                }
+                let searched = this.update(cx, |this, cx| {
+                    let matches = chunk
+                        .into_iter()
+                        .filter(|(_, line)| {
+                            this.line_passes_filters(line) && this.line_matches_query(line)
+                        })
+                        .map(|(in_old_log, line)| OlderMatch { in_old_log, line })
+                        .collect::<Vec<_>>();
+                    if !matches.is_empty() {
+                        this.older_matches.extend(matches);
+                        cx.notify();
+                    }
+                });
+                if searched.is_err() {
+                    return;
+                }
+                smol::future::yield_now().await;
+            }
+            this.update(cx, |this, cx| {
+                this.full_file_search_task = None;
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+
+    fn record_trimmed_lines(&mut self, dropped_line_count: usize) {
+        if dropped_line_count == 0 {
+            return;
+        }
+        self.trimmed_line_count += dropped_line_count;
+        // Whichever entry is now first gains the header saying that older ones were trimmed.
+        if !self.filtered_indices.is_empty() {
+            self.list_state.remeasure_items(0..1);
+        }
+    }
+
+    /// Updates per-line state after `dropped_line_count` lines were popped off the front of `lines`.
+    fn shift_line_indices(&mut self, dropped_line_count: usize) {
+        if dropped_line_count == 0 {
+            return;
+        }
+        self.current_match = self
+            .current_match
+            .and_then(|line_index| line_index.checked_sub(dropped_line_count));
+        self.latest_error_line = self
+            .latest_error_line
+            .and_then(|line_index| line_index.checked_sub(dropped_line_count));
+        self.current_error_line = self
+            .current_error_line
+            .and_then(|line_index| line_index.checked_sub(dropped_line_count));
+        self.search_bar_active_line = self
+            .search_bar_active_line
+            .and_then(|line_index| line_index.checked_sub(dropped_line_count));
+        self.last_viewed_line_count = self
+            .last_viewed_line_count
+            .map(|line_count| line_count.saturating_sub(dropped_line_count));
+        self.new_lines_start = self
+            .new_lines_start
+            .map(|line_index| line_index.saturating_sub(dropped_line_count));
+        // A selection that was only partly dropped is clamped to the first remaining line.
+        if let Some(selection) = self.selected_line_range() {
+            if selection.end <= dropped_line_count {
+                self.selection_anchor = None;
+                self.selection_head = None;
+            } else {
+                self.selection_anchor = self
+                    .selection_anchor
+                    .map(|line_index| line_index.saturating_sub(dropped_line_count));
+                self.selection_head = self
+                    .selection_head
+                    .map(|line_index| line_index.saturating_sub(dropped_line_count));
+            }
+        }
+        for line_indices in [
+            &mut self.expanded_lines,
+            &mut self.collapsed_lines,
+            &mut self.pinned_lines,
+            &mut self.json_expanded_lines,
+            &mut self.full_length_lines,
+        ] {
+            *line_indices = line_indices
+                .iter()
+                .filter_map(|line_index| line_index.checked_sub(dropped_line_count))
+                .collect();
+        }
+    }
+
+    /// Whether the current query is matched fuzzily. Regex queries are always matched as regexes.
+    fn is_fuzzy_query(&self) -> bool {
+        self.fuzzy_match
+            && !self.filter.fuzzy_query.is_empty()
+            && regex_pattern(&self.filter.query).is_none()
+    }
+
+    /// Returns the non-overlapping byte ranges of `line` matched by the current query.
+    fn match_ranges(&self, line: &str) -> Vec<Range<usize>> {
+        if self.is_fuzzy_query() {
+            return Vec::new();
+        }
+        self.filter.match_ranges(line)
+    }
+
+    /// A line matches when either it or one of its continuation lines matches the query, so a
+    /// matching stack frame keeps its header visible. With `invert_filter`, only lines where
+    /// neither matches do. Excluded terms are only checked against the header, so a stack frame
+    /// mentioning an excluded module doesn't hide the entry it belongs to.
+    fn line_matches_query(&self, line: &LogLine) -> bool {
+        if self.filter.query.is_empty() {
+            return true;
+        }
+
+        let matches = self.filter.matches(&line.text, &line.lowercase_text)
+            || (!self.filter.is_excluded(&line.lowercase_text)
+                && line
+                    .continuation_lines
+                    .iter()
+                    .zip(&line.lowercase_continuation_lines)
+                    .any(|(continuation_line, lowercase_continuation_line)| {
+                        self.filter
+                            .matches_query(continuation_line, lowercase_continuation_line)
+                    }));
+        matches != self.invert_filter
+    }
+
+    fn time_matches_filter(&self, line: &str) -> bool {
+        let Some(time_window) = self.time_window else {
+            return true;
+        };
+        match parse_timestamp(line) {
+            Some(timestamp) => timestamp >= time_window.since,
+            None => self.keep_untimed_lines,
+        }
+    }
+
+    fn line_passes_filters(&self, line: &LogLine) -> bool {
+        let text = strip_ansi(&line.text);
+        self.filter.matches_level(&text)
+            && self.time_matches_filter(&text)
+            && self.target_matches_filter(&text)
+            && self.host_matches_filter(&text)
+            && !self.is_excluded_by_exclusion_query(line)
+    }
+
+    fn is_excluded_by_exclusion_query(&self, line: &LogLine) -> bool {
+        self.exclusion_terms.iter().any(|term| {
+            term_match_ranges(&line.lowercase_text, term, self.filter.whole_word)
+                .next()
+                .is_some()
+        })
+    }
+
+    fn host_matches_filter(&self, line: &str) -> bool {
+        self.host_filter
+            .is_none_or(|host| LogHost::of_line(line) == host)
+    }
+
+    fn target_matches_filter(&self, line: &str) -> bool {
+        if self.muted_targets.is_empty() && self.shown_targets.is_empty() {
+            return true;
+        }
+        let target = parse_log_target(line);
+        (self.shown_targets.is_empty()
+            || target.is_some_and(|target| self.shown_targets.contains(target)))
+            && target.is_none_or(|target| !self.muted_targets.contains(target))
+    }
+
+    /// Tests the line at `line_index` against the filters, appending it to `filtered_indices`
+    /// and `match_line_indices` as appropriate. Lines must be pushed in order, as the context
+    /// around a match is filled in from the lines pushed before it.
+    fn push_filtered_line(&mut self, line_index: usize) {
+        let Some(line) = self.lines.get(line_index) else {
+            return;
+        };
+        let passes_filters = self.line_passes_filters(line);
+        let matches_query = passes_filters && self.line_matches_query(line);
+        if matches_query && !self.filter.query.is_empty() {
+            self.match_line_indices.push(line_index);
+        }
+        let context_line_count = self.active_filter_context_line_count();
+        if matches_query || (passes_filters && self.show_non_matching) {
+            if context_line_count > 0 {
+                let first_unlisted_line = self
+                    .filtered_indices
+                    .last()
+                    .map_or(0, |&last_line_index| last_line_index + 1);
+                let first_context_line = line_index
+                    .saturating_sub(context_line_count)
+                    .max(first_unlisted_line);
+                self.filtered_indices.extend(first_context_line..line_index);
+            }
+            self.filtered_indices.push(line_index);
+        } else if context_line_count > 0
+            && self
+                .match_line_indices
+                .last()
+                .is_some_and(|&match_line_index| {
+                    line_index - match_line_index <= context_line_count
+                })
+        {
+            self.filtered_indices.push(line_index);
+        }
+    }
+
+    /// The number of lines of context shown around matches, which is 0 unless the query hides
+    /// the lines that don't match it.
+    fn active_filter_context_line_count(&self) -> usize {
+        if self.filter.query.is_empty() || self.show_non_matching {
+            0
+        } else {
+            self.filter_context_line_count
+        }
+    }
+
+    /// Whether the line at `line_index` is only shown as context around a match.
+    fn is_filter_context_line(&self, line_index: usize) -> bool {
+        self.active_filter_context_line_count() > 0
+            && self.match_line_indices.binary_search(&line_index).is_err()
+    }
+
+    pub fn set_filter_context_line_count(&mut self, line_count: usize, cx: &mut Context<Self>) {
+        self.filter_context_line_count = line_count;
+        self.recompute_filtered_indices();
+        cx.emit(OpenLogEvent::StateChanged);
+        cx.notify();
+    }
+
+    /// Brings `filtered_indices` up to date after `dropped_line_count` lines were popped off the
+    /// front of `lines` and the lines from `first_changed_line_index` on were added or gained
+    /// continuation lines. Only those lines are tested against the filter; the rest keep their
+    /// place, so the list's scroll position carries over.
+    fn update_filtered_indices(
+        &mut self,
+        first_changed_line_index: usize,
+        dropped_line_count: usize,
+    ) {
+        if self.is_fuzzy_query() {
+            self.update_fuzzy_filtered_indices(first_changed_line_index, dropped_line_count);
+            return;
+        }
+
+        let trimmed_count = trim_line_indices(&mut self.filtered_indices, dropped_line_count);
+        trim_line_indices(&mut self.match_line_indices, dropped_line_count);
+        self.list_state.splice(0..trimmed_count, 0);
+
+        let previous_len = self.filtered_indices.len();
+        let first_changed_item_ix =
+            truncate_line_indices(&mut self.filtered_indices, first_changed_line_index);
+        truncate_line_indices(&mut self.match_line_indices, first_changed_line_index);
+        for line_index in first_changed_line_index..self.lines.len() {
+            self.push_filtered_line(line_index);
+        }
+        self.list_state.splice(
+            first_changed_item_ix..previous_len,
+            self.filtered_indices.len() - first_changed_item_ix,
+        );
+        self.level_density = None;
+    }
+
+    /// Like `update_filtered_indices`, for a fuzzy query. Fuzzy matches are ordered by score, so
+    /// only the changed lines are matched and each match is inserted after the lines scoring at
+    /// least as well, instead of matching the whole buffer again on every append.
+    fn update_fuzzy_filtered_indices(
+        &mut self,
+        first_changed_line_index: usize,
+        dropped_line_count: usize,
+    ) {
+        let is_stale = |line_index: usize| {
+            line_index < dropped_line_count
+                || line_index - dropped_line_count >= first_changed_line_index
+        };
+        // Walk backwards so removing an item doesn't shift the ones still to be visited.
+        for item_ix in (0..self.filtered_indices.len()).rev() {
+            let line_index = self.filtered_indices[item_ix];
+            if is_stale(line_index) {
+                self.filtered_indices.remove(item_ix);
+                self.list_state.splice(item_ix..item_ix + 1, 0);
+            } else {
+                self.filtered_indices[item_ix] = line_index - dropped_line_count;
+            }
+        }
+        trim_line_indices(&mut self.match_line_indices, dropped_line_count);
+        truncate_line_indices(&mut self.match_line_indices, first_changed_line_index);
+        self.fuzzy_match_ranges = std::mem::take(&mut self.fuzzy_match_ranges)
+            .into_iter()
+            .filter(|(line_index, _)| !is_stale(*line_index))
+            .map(|(line_index, ranges)| (line_index - dropped_line_count, ranges))
+            .collect();
+        self.fuzzy_match_scores = std::mem::take(&mut self.fuzzy_match_scores)
+            .into_iter()
+            .filter(|(line_index, _)| !is_stale(*line_index))
+            .map(|(line_index, score)| (line_index - dropped_line_count, score))
+            .collect();
+
+        let candidates = self.fuzzy_candidates(first_changed_line_index);
+        let matches = self.fuzzy_matches(&candidates);
+        let matched_line_indices = matches
+            .iter()
+            .map(|string_match| string_match.candidate_id)
+            .collect::<HashSet<_>>();
+
+        if self.invert_filter {
+            for candidate in &candidates {
+                let matches_query = !matched_line_indices.contains(&candidate.id);
+                if matches_query {
+                    self.match_line_indices.push(candidate.id);
+                }
+                if matches_query || self.show_non_matching {
+                    let item_ix = self.filtered_indices.len();
+                    self.filtered_indices.push(candidate.id);
+                    self.list_state.splice(item_ix..item_ix, 1);
+                }
+            }
+        } else {
+            for string_match in &matches {
+                // Matches lead the list, best first; lines that don't match trail them.
+                let matched_count = self.match_line_indices.len();
+                let item_ix =
+                    self.filtered_indices[..matched_count].partition_point(|line_index| {
+                        self.fuzzy_match_scores
+                            .get(line_index)
+                            .is_some_and(|&score| score >= string_match.score)
+                    });
+                self.filtered_indices
+                    .insert(item_ix, string_match.candidate_id);
+                self.list_state.splice(item_ix..item_ix, 1);
+                self.match_line_indices.push(string_match.candidate_id);
+                self.fuzzy_match_ranges
+                    .insert(string_match.candidate_id, string_match.ranges().collect());
+                self.fuzzy_match_scores
+                    .insert(string_match.candidate_id, string_match.score);
+            }
+            self.match_line_indices.sort_unstable();
+            if self.show_non_matching {
+                for candidate in &candidates {
+                    if !matched_line_indices.contains(&candidate.id) {
+                        let item_ix = self.filtered_indices.len();
+                        self.filtered_indices.push(candidate.id);
+                        self.list_state.splice(item_ix..item_ix, 1);
+                    }
+                }
+            }
+        }
+        self.level_density = None;
+    }
+
+    fn recompute_filtered_indices(&mut self) {
+        self.recompute_filtered_indices_after_drop(0);
+    }
+
+    /// Rebuilds `filtered_indices` while keeping the line at the top of the viewport in place.
+    /// `dropped_line_count` is the number of lines popped off the front of `lines` since the
+    /// indices were last computed, so the previous top line can still be located.
+    fn recompute_filtered_indices_after_drop(&mut self, dropped_line_count: usize) {
+        let scroll_top = self.list_state.logical_scroll_top();
+        let anchor = self
+            .filtered_indices
+            .get(scroll_top.item_ix)
+            .map(|&line_index| line_index.checked_sub(dropped_line_count));
+
+        self.filtered_indices.clear();
+        self.match_line_indices.clear();
+        self.fuzzy_match_ranges.clear();
+        self.fuzzy_match_scores.clear();
+        let is_fuzzy_query = self.is_fuzzy_query();
+        if is_fuzzy_query {
+            let fuzzy_candidates = self.fuzzy_candidates(0);
+            self.apply_fuzzy_matches(&fuzzy_candidates);
+        } else {
+            for line_index in 0..self.lines.len() {
+                self.push_filtered_line(line_index);
+            }
+        }
+        self.list_state.reset(self.filtered_indices.len());
+        self.level_density = None;
+
+        // Without an anchor the list was showing its tail, which `reset` preserves.
+        let Some(anchor_line_index) = anchor else {
+            return;
+        };
+        let (item_ix, offset_in_item) = match anchor_line_index {
+            Some(anchor_line_index) => {
+                let item_ix = if is_fuzzy_query {
+                    self.filtered_indices
+                        .iter()
+                        .position(|&line_index| line_index == anchor_line_index)
+                        .unwrap_or(0)
+                } else {
+                    self.filtered_indices
+                        .partition_point(|&line_index| line_index < anchor_line_index)
+                };
+                let offset_in_item =
+                    if self.filtered_indices.get(item_ix) == Some(&anchor_line_index) {
+                        scroll_top.offset_in_item
+                    } else {
+                        px(0.)
+                    };
+                (item_ix, offset_in_item)
+            }
+            None => (0, px(0.)),
+        };
+        if item_ix < self.filtered_indices.len() {
+            self.list_state.scroll_to(ListOffset {
+                item_ix,
+                offset_in_item,
+            });
+        }
+    }
+
+    /// The fuzzy match candidates for the lines from `first_line_index` on that pass the filters.
+    fn fuzzy_candidates(&self, first_line_index: usize) -> Vec<StringMatchCandidate> {
+        self.lines
+            .iter()
+            .enumerate()
+            .skip(first_line_index)
+            .filter(|(_, line)| self.line_passes_filters(line))
+            .map(|(idx, line)| StringMatchCandidate::new(idx, &strip_ansi(&line.text)))
+            .collect()
+    }
+
+    /// Fuzzy matches the query against `candidates`, best first, leaving out excluded lines.
+    fn fuzzy_matches(&self, candidates: &[StringMatchCandidate]) -> Vec<StringMatch> {
+        let mut matches = self.foreground_executor.block_on(fuzzy::match_strings(
+            candidates,
+            &self.filter.fuzzy_query,
+            true,
+            false,
+            usize::MAX,
+            &AtomicBool::default(),
+            self.background_executor.clone(),
+        ));
+        matches
+            .retain(|string_match| !self.filter.is_excluded(&string_match.string.to_lowercase()));
+        matches
+    }
+
+    /// Fills `filtered_indices` from fuzzy matching the query against `candidates`, with the best
+    /// matches first. Lines that don't match follow in file order when `show_non_matching` is set.
+    fn apply_fuzzy_matches(&mut self, candidates: &[StringMatchCandidate]) {
+        let matches = self.fuzzy_matches(candidates);
+        let matched_line_indices = matches
+            .iter()
+            .map(|string_match| string_match.candidate_id)
+            .collect::<HashSet<_>>();
+
+        if self.invert_filter {
+            for candidate in candidates {
+                let matches_query = !matched_line_indices.contains(&candidate.id);
+                if matches_query {
+                    self.match_line_indices.push(candidate.id);
+                }
+                if matches_query || self.show_non_matching {
+                    self.filtered_indices.push(candidate.id);
+                }
+            }
+            return;
+        }
+
+        self.filtered_indices
+            .extend(matches.iter().map(|string_match| string_match.candidate_id));
+        self.match_line_indices = self.filtered_indices.clone();
+        self.match_line_indices.sort_unstable();
+        if self.show_non_matching {
+            self.filtered_indices.extend(
+                candidates
+                    .iter()
+                    .map(|candidate| candidate.id)
+                    .filter(|line_index| !matched_line_indices.contains(line_index)),
+            );
+        }
+        self.fuzzy_match_ranges = matches
+            .iter()
+            .map(|string_match| (string_match.candidate_id, string_match.ranges().collect()))
+            .collect();
+        self.fuzzy_match_scores = matches
+            .iter()
+            .map(|string_match| (string_match.candidate_id, string_match.score))
+            .collect();
+    }
+
+    fn tab_status(&self) -> TabStatus {
+        TabStatus {
+            watcher_disconnected: self.watcher_disconnected,
+            streaming: self.following && !self.watcher_disconnected && !self.waiting_for_file,
+            match_count: self.is_filtered().then(|| self.match_counts().0),
+        }
+    }
+
+    /// Returns the number of lines passing the current filter and the total number of lines.
+    pub fn match_counts(&self) -> (usize, usize) {
+        let match_count = if self.filter.query.is_empty() {
+            self.filtered_indices.len()
+        } else {
+            self.match_line_indices.len()
+        };
+        (match_count, self.lines.len())
+    }
+
+    pub fn is_filtered(&self) -> bool {
+        self.filter.level_filter != LevelMask::ALL || self.has_non_level_filters()
+    }
+
+    /// Whether any filter other than the level filter is active.
+    fn has_non_level_filters(&self) -> bool {
+        !self.filter.query.is_empty()
+            || self.time_window.is_some()
+            || !self.muted_targets.is_empty()
+            || !self.shown_targets.is_empty()
+            || self.host_filter.is_some()
+            || !self.exclusion_terms.is_empty()
+    }
+
+    pub fn search_query(&self) -> &str {
+        &self.filter.query
+    }
+
+    pub fn exclusion_query(&self) -> &str {
+        &self.exclusion_query
+    }
+
+    pub fn set_exclusion_query(&mut self, query: String, cx: &mut Context<Self>) {
+        if query == self.exclusion_query {
+            return;
+        }
+        self.exclusion_terms = parse_query_terms(&query);
+        self.exclusion_query = query;
+        self.current_match = None;
+        self.recompute_filtered_indices();
+        cx.emit(OpenLogEvent::StateChanged);
+        cx.notify();
+    }
+
+    pub fn set_search_query(&mut self, query: String, cx: &mut Context<Self>) {
+        if query == self.filter.query {
+            return;
+        }
+
+        let kvp = KeyValueStore::global(cx);
+        let persisted_query = query.clone();
+        db::write_and_log(cx, move || async move {
+            let scope = kvp.scoped(OPEN_LOG_VIEW_NAMESPACE);
+            if persisted_query.is_empty() {
+                scope.delete(SEARCH_QUERY_KEY.to_string()).await
+            } else {
+                scope
+                    .write(SEARCH_QUERY_KEY.to_string(), persisted_query)
+                    .await
+            }
+        });
+
+        self.apply_search_query(query);
+        self.refresh_full_file_search(cx);
+        cx.emit(OpenLogEvent::StateChanged);
+        cx.notify();
+    }
+
+    fn apply_search_query(&mut self, query: String) {
+        self.filter.set_query(query);
+        self.current_match = None;
+        self.recompute_filtered_indices();
+        if self.is_fuzzy_query() {
+            self.list_state.scroll_to(ListOffset {
+                item_ix: 0,
+                offset_in_item: px(0.),
+            });
+        }
+    }
+
+    pub fn is_fuzzy_match(&self) -> bool {
+        self.fuzzy_match
+    }
+
+    pub fn toggle_fuzzy_match(&mut self, cx: &mut Context<Self>) {
+        self.fuzzy_match = !self.fuzzy_match;
+        self.current_match = None;
+        self.recompute_filtered_indices();
+        if self.is_fuzzy_query() {
+            self.list_state.scroll_to(ListOffset {
+                item_ix: 0,
+                offset_in_item: px(0.),
+            });
+        }
+        cx.emit(OpenLogEvent::StateChanged);
+        cx.notify();
+    }
+
+    pub fn is_whole_word(&self) -> bool {
+        self.filter.whole_word
+    }
+
+    pub fn toggle_whole_word(&mut self, cx: &mut Context<Self>) {
+        self.filter.set_whole_word(!self.filter.whole_word);
+        self.current_match = None;
+        self.recompute_filtered_indices();
+        self.refresh_full_file_search(cx);
+        cx.emit(OpenLogEvent::StateChanged);
+        cx.notify();
+    }
+
+    pub fn toggle_level(&mut self, level: LogLevel, cx: &mut Context<Self>) {
+        self.filter.level_filter.toggle(level);
+        self.recompute_filtered_indices();
+        cx.emit(OpenLogEvent::StateChanged);
+        cx.notify();
+    }
+
+    /// Shows only lines at `level`, or every level if that's already the case.
+    pub fn toggle_level_only(&mut self, level: LogLevel, cx: &mut Context<Self>) {
+        self.filter.level_filter = if self.filter.level_filter == LevelMask::only(level) {
+            LevelMask::ALL
+        } else {
+            LevelMask::only(level)
+        };
+        self.recompute_filtered_indices();
+        cx.emit(OpenLogEvent::StateChanged);
+        cx.notify();
+    }
+
+    /// Removes everything hiding lines: the query, the exclusions and the level, time, target and
+    /// host filters.
+    fn clear_filters(&mut self, cx: &mut Context<Self>) {
+        self.filter.level_filter = LevelMask::ALL;
+        self.time_window = None;
+        self.muted_targets.clear();
+        self.shown_targets.clear();
+        self.host_filter = None;
+        self.exclusion_query.clear();
+        self.exclusion_terms.clear();
+        if self.filter.query.is_empty() {
+            self.recompute_filtered_indices();
+            cx.emit(OpenLogEvent::StateChanged);
+            cx.notify();
+        } else {
+            self.set_search_query(String::new(), cx);
+        }
+        cx.emit(OpenLogEvent::FilterCleared);
+    }
+
+    /// Replaces the query and level filter with those of a filter preset.
+    fn apply_filter_preset(&mut self, preset: &FilterPreset, cx: &mut Context<Self>) {
+        self.filter.level_filter = preset.level_filter;
+        if preset.query == self.filter.query {
+            self.recompute_filtered_indices();
+            cx.emit(OpenLogEvent::StateChanged);
+            cx.notify();
+        } else {
+            self.set_search_query(preset.query.clone(), cx);
+        }
+    }
+
+    /// Shows only lines logged within `duration` of now, or all lines when `None`.
+    pub fn set_time_window(&mut self, duration: Option<TimeDelta>, cx: &mut Context<Self>) {
+        self.time_window = duration.map(|duration| TimeWindow {
+            duration,
+            since: Local::now().fixed_offset() - duration,
+        });
+        self.recompute_filtered_indices();
+        cx.emit(OpenLogEvent::StateChanged);
+        cx.notify();
+    }
+
+    pub fn toggle_keep_untimed_lines(&mut self, cx: &mut Context<Self>) {
+        self.keep_untimed_lines = !self.keep_untimed_lines;
+        if self.time_window.is_some() {
+            self.recompute_filtered_indices();
+            cx.emit(OpenLogEvent::StateChanged);
+        }
+        cx.notify();
+    }
+
+    pub fn is_filter_inverted(&self) -> bool {
+        self.invert_filter
+    }
+
+    pub fn toggle_invert_filter(&mut self, cx: &mut Context<Self>) {
+        self.invert_filter = !self.invert_filter;
+        self.current_match = None;
+        self.recompute_filtered_indices();
+        cx.emit(OpenLogEvent::StateChanged);
+        cx.notify();
+    }
+
+    pub fn toggle_show_non_matching(&mut self, cx: &mut Context<Self>) {
+        self.show_non_matching = !self.show_non_matching;
+        self.recompute_filtered_indices();
+        cx.emit(OpenLogEvent::StateChanged);
+        cx.notify();
+    }
+
+    /// Whether the project is on a remote server, whose log messages are relayed into the local
+    /// log and can be told apart from the client's own.
+    pub fn is_remote_project(&self, cx: &App) -> bool {
+        self.project.read(cx).is_via_remote_server()
+    }
+
+    pub fn host_filter(&self) -> Option<LogHost> {
+        self.host_filter
+    }
+
+    pub fn set_host_filter(&mut self, host_filter: Option<LogHost>, cx: &mut Context<Self>) {
+        if host_filter == self.host_filter {
+            return;
+        }
+        self.host_filter = host_filter;
+        self.current_match = None;
+        self.recompute_filtered_indices();
+        cx.emit(OpenLogEvent::StateChanged);
+        cx.notify();
+    }
+
+    /// Returns the targets to offer as quick filters, most frequent first, with whether each is
+    /// shown. Shown targets are always included, even once their lines were trimmed.
+    pub fn target_chips(&self) -> Vec<(String, bool)> {
+        let mut chips = self
+            .target_counts
+            .most_frequent(TARGET_CHIP_LIMIT)
+            .into_iter()
+            .map(|target| {
+                let is_shown = self.shown_targets.contains(&target);
+                (target, is_shown)
+            })
+            .collect::<Vec<_>>();
+        let mut other_shown_targets = self
+            .shown_targets
+            .iter()
+            .filter(|target| !chips.iter().any(|(chip, _)| chip == *target))
+            .cloned()
+            .collect::<Vec<_>>();
+        other_shown_targets.sort_unstable();
+        chips.extend(other_shown_targets.into_iter().map(|target| (target, true)));
+        chips
+    }
+
+    pub fn toggle_target_shown(&mut self, target: &str, cx: &mut Context<Self>) {
+        if !self.shown_targets.remove(target) {
+            self.shown_targets.insert(target.to_string());
+        }
+        self.current_match = None;
+        self.recompute_filtered_indices();
+        cx.emit(OpenLogEvent::StateChanged);
+        cx.notify();
+    }
+
+    /// Shows only the lines from the target of the selected line, keeping that line in view. When
+    /// it's already the only target shown, or the line has none, every target is shown again.
+    pub fn toggle_only_selected_target(&mut self, cx: &mut Context<Self>) {
+        let Some(line_index) = self.selection_head else {
+            return;
+        };
+        let target = self
+            .lines
+            .get(line_index)
+            .and_then(|line| parse_log_target(&strip_ansi(&line.text)).map(str::to_string));
+        match target {
+            Some(target)
+                if !(self.shown_targets.len() == 1 && self.shown_targets.contains(&target)) =>
+            {
+                self.shown_targets = HashSet::from_iter([target]);
+            }
+            _ if self.shown_targets.is_empty() => return,
+            _ => self.shown_targets.clear(),
+        }
+        self.current_match = None;
+        self.recompute_filtered_indices();
+        if let Some(filtered_index) = self.filtered_index_of(line_index) {
+            self.reveal_entry(filtered_index, cx);
+        }
+        cx.emit(OpenLogEvent::StateChanged);
+        cx.notify();
+    }
+
+    /// Shows the lines from every target again. Returns whether any were hidden.
+    pub fn show_all_targets(&mut self, cx: &mut Context<Self>) -> bool {
+        if self.shown_targets.is_empty() {
+            return false;
+        }
+        self.shown_targets.clear();
+        self.current_match = None;
+        self.recompute_filtered_indices();
+        cx.emit(OpenLogEvent::StateChanged);
+        cx.notify();
+        true
+    }
+
+    /// Returns the muted targets in alphabetical order.
+    pub fn muted_targets(&self) -> Vec<String> {
+        let mut targets = self.muted_targets.iter().cloned().collect::<Vec<_>>();
+        targets.sort_unstable();
+        targets
+    }
+
+    pub fn toggle_target_muted(&mut self, target: &str, cx: &mut Context<Self>) {
+        if !self.muted_targets.remove(target) {
+            self.muted_targets.insert(target.to_string());
+        }
+        self.current_match = None;
+        self.recompute_filtered_indices();
+        cx.emit(OpenLogEvent::StateChanged);
+        cx.notify();
+    }
+
+    pub fn unmute_all_targets(&mut self, cx: &mut Context<Self>) {
+        if self.muted_targets.is_empty() {
+            return;
+        }
+        self.muted_targets.clear();
+        self.current_match = None;
+        self.recompute_filtered_indices();
+        cx.emit(OpenLogEvent::StateChanged);
+        cx.notify();
+    }
+
+    pub fn select_next_match(&mut self, cx: &mut Context<Self>) {
+        let Some(&first_match) = self.match_line_indices.first() else {
+            return;
+        };
+        let match_index = match self.current_match {
+            Some(current_match) => self
+                .match_line_indices
+                .partition_point(|&line_index| line_index <= current_match),
+            None => {
+                let top_line_index = self.top_visible_line_index();
+                self.match_line_indices
+                    .partition_point(|&line_index| line_index < top_line_index)
+            }
+        };
+        let next_match = self
+            .match_line_indices
+            .get(match_index)
+            .copied()
+            .unwrap_or(first_match);
+        self.select_match(next_match, cx);
+    }
+
+    pub fn select_previous_match(&mut self, cx: &mut Context<Self>) {
+        let Some(&last_match) = self.match_line_indices.last() else {
+            return;
+        };
+        let current_match = self
+            .current_match
+            .unwrap_or_else(|| self.top_visible_line_index());
+        let match_index = self
+            .match_line_indices
+            .partition_point(|&line_index| line_index < current_match);
+        let previous_match = match_index
+            .checked_sub(1)
+            .and_then(|match_index| self.match_line_indices.get(match_index))
+            .copied()
+            .unwrap_or(last_match);
+        self.select_match(previous_match, cx);
+    }
+
+    /// Returns the raw text of the selected match, or of the line at the top of the viewport.
+    pub fn selected_line_text(&self) -> Option<SharedString> {
+        let line_index = match self.current_match {
+            Some(line_index) => line_index,
+            None => *self
+                .filtered_indices
+                .get(self.list_state.logical_scroll_top().item_ix)?,
+        };
+        self.lines.get(line_index).map(|line| line.text.clone())
+    }
+
+    fn top_visible_line_index(&self) -> usize {
+        self.filtered_indices
+            .get(self.list_state.logical_scroll_top().item_ix)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Returns the position of `line_index` in `filtered_indices`, if the line is visible.
+    fn filtered_index_of(&self, line_index: usize) -> Option<usize> {
+        if self.is_fuzzy_query() {
+            self.filtered_indices
+                .iter()
+                .position(|&filtered_line_index| filtered_line_index == line_index)
+        } else {
+            self.filtered_indices.binary_search(&line_index).ok()
+        }
+    }
+
+    fn select_match(&mut self, line_index: usize, cx: &mut Context<Self>) {
+        self.current_match = Some(line_index);
+        if let Some(filtered_index) = self.filtered_index_of(line_index) {
+            self.reveal_entry(filtered_index, cx);
+        }
+        cx.notify();
+    }
+
+    pub fn scroll_to_top(&mut self, cx: &mut Context<Self>) {
+        self.scroll_to_entry(0, cx);
+        cx.notify();
+    }
+
+    /// Scrolls the list so the entry at `filtered_index` is at the top. Entries that haven't been
+    /// laid out have no position to ease towards, so jumps to them snap even with `smooth_scroll`
+    /// on. They're far enough away that easing through the rows between would only be a blur.
+    fn scroll_to_entry(&mut self, filtered_index: usize, cx: &mut Context<Self>) {
+        let distance = self
+            .list_state
+            .bounds_for_item(filtered_index)
+            .map(|bounds| bounds.top() - self.list_state.viewport_bounds().top());
+        match distance {
+            Some(distance) if self.should_scroll_smoothly(cx) => self.animate_scroll(distance, cx),
+            _ => {
+                self._scroll_animation = None;
+                self.list_state.scroll_to(ListOffset {
+                    item_ix: filtered_index,
+                    offset_in_item: px(0.),
+                });
+            }
+        }
+    }
+
+    /// Scrolls the list just far enough for the entry at `filtered_index` to be fully visible.
+    fn reveal_entry(&mut self, filtered_index: usize, cx: &mut Context<Self>) {
+        let viewport = self.list_state.viewport_bounds();
+        let distance = self
+            .list_state
+            .bounds_for_item(filtered_index)
+            .map(|bounds| {
+                if bounds.top() < viewport.top() {
+                    bounds.top() - viewport.top()
+                } else if bounds.bottom() > viewport.bottom() {
+                    (bounds.bottom() - viewport.bottom()).min(bounds.top() - viewport.top())
+                } else {
+                    px(0.)
+                }
+            });
+        match distance {
+            Some(distance) if self.should_scroll_smoothly(cx) => self.animate_scroll(distance, cx),
+            _ => {
+                self._scroll_animation = None;
+                self.list_state.scroll_to_reveal_item(filtered_index);
+            }
+        }
+    }
+
+    fn should_scroll_smoothly(&self, cx: &App) -> bool {
+        LogViewSettings::get_global(cx).smooth_scroll && !cx.reduce_motion()
+    }
+
+    fn animate_scroll(&mut self, distance: Pixels, cx: &mut Context<Self>) {
+        if distance == px(0.) {
+            self._scroll_animation = None;
+            return;
+        }
+        self._scroll_animation = Some(cx.spawn(async move |this, cx| {
+            let start = Instant::now();
+            let mut scrolled = px(0.);
+            loop {
+                cx.background_executor()
+                    .timer(SMOOTH_SCROLL_FRAME_INTERVAL)
+                    .await;
+                let progress =
+                    (start.elapsed().as_secs_f32() / SMOOTH_SCROLL_DURATION.as_secs_f32()).min(1.);
+                let target = distance * ease_in_out(progress);
+                let step = target - scrolled;
+                scrolled = target;
+                let updated = this.update(cx, |this, cx| {
+                    this.list_state.scroll_by(step);
+                    cx.notify();
+                });
+                if updated.is_err() || progress >= 1. {
+                    break;
+                }
+            }
+        }));
+    }
+
+    pub fn scroll_to_bottom(&mut self, cx: &mut Context<Self>) {
+        self.set_following(true, cx);
+        self.unseen_line_count = 0;
+        self.list_state.scroll_to_end();
+        cx.notify();
+    }
+
+    pub fn toggle_line_numbers(&mut self, cx: &mut Context<Self>) {
+        self.show_line_numbers = !self.show_line_numbers;
+        cx.notify();
+    }
+
+    pub fn toggle_context_pane(&mut self, cx: &mut Context<Self>) {
+        self.show_context_pane = !self.show_context_pane;
+        cx.notify();
+    }
+
+    fn text_size(&self, cx: &App) -> Rems {
+        TextSize::Editor.rems(cx) * self.font_scale
+    }
+
+    pub fn set_font_scale(&mut self, font_scale: f32, cx: &mut Context<Self>) {
+        let font_scale = font_scale.clamp(MIN_FONT_SCALE, MAX_FONT_SCALE);
+        if font_scale == self.font_scale {
+            return;
+        }
+        self.font_scale = font_scale;
+
+        let kvp = KeyValueStore::global(cx);
+        db::write_and_log(cx, move || async move {
+            kvp.scoped(OPEN_LOG_VIEW_NAMESPACE)
+                .write(FONT_SCALE_KEY.to_string(), font_scale.to_string())
+                .await
+        });
+
+        self.list_state.remeasure();
+        cx.notify();
+    }
+
+    pub fn toggle_compact(&mut self, cx: &mut Context<Self>) {
+        self.compact = !self.compact;
+        let compact = self.compact;
+
+        let kvp = KeyValueStore::global(cx);
+        db::write_and_log(cx, move || async move {
+            kvp.scoped(OPEN_LOG_VIEW_NAMESPACE)
+                .write(COMPACT_KEY.to_string(), compact.to_string())
+                .await
+        });
+
+        self.list_state.remeasure();
+        cx.notify();
+    }
+
+    pub fn toggle_wrap_lines(&mut self, cx: &mut Context<Self>) {
+        self.wrap_lines = !self.wrap_lines;
+        self.expanded_lines.clear();
+        self.list_state.remeasure();
+        cx.notify();
+    }
+
+    fn toggle_line_expanded(
+        &mut self,
+        line_index: usize,
+        filtered_index: usize,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.expanded_lines.remove(&line_index) {
+            self.expanded_lines.insert(line_index);
+        }
+        self.list_state
+            .remeasure_items(filtered_index..filtered_index + 1);
+        cx.notify();
+    }
+
+    fn toggle_json_expanded(
+        &mut self,
+        line_index: usize,
+        filtered_index: usize,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.json_expanded_lines.remove(&line_index) {
+            self.json_expanded_lines.insert(line_index);
+        }
+        self.list_state
+            .remeasure_items(filtered_index..filtered_index + 1);
+        cx.notify();
+    }
+
+    fn toggle_full_length(
+        &mut self,
+        line_index: usize,
+        filtered_index: usize,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.full_length_lines.remove(&line_index) {
+            self.full_length_lines.insert(line_index);
+        }
+        self.list_state
+            .remeasure_items(filtered_index..filtered_index + 1);
+        cx.notify();
+    }
+
+    /// Selects the line on a plain click, or extends the selection to it on a shift-click.
+    fn click_line(&mut self, line_index: usize, extend: bool, cx: &mut Context<Self>) {
+        if !extend || self.selection_anchor.is_none() {
+            self.selection_anchor = Some(line_index);
+        }
+        self.selection_head = Some(line_index);
+        cx.notify();
+    }
+
+    /// Moves the selection `delta` visible lines down, or up when negative, collapsing it to a
+    /// single line and scrolling it into view. Without a selection, this starts from the line at
+    /// the top of the viewport.
+    fn move_selection(&mut self, delta: isize, cx: &mut Context<Self>) {
+        let fallback = self.list_state.logical_scroll_top().item_ix;
+        let Some(filtered_index) =
+            step_filtered_index(&self.filtered_indices, self.selection_head, fallback, delta)
+        else {
+            return;
+        };
+        let Some(&line_index) = self.filtered_indices.get(filtered_index) else {
+            return;
+        };
+        self.selection_anchor = Some(line_index);
+        self.selection_head = Some(line_index);
+        self.reveal_entry(filtered_index, cx);
+        cx.notify();
+    }
+
+    /// The number of entries that fit in the viewport, by which page movements step.
+    fn page_entry_count(&self) -> usize {
+        let viewport = self.list_state.viewport_bounds();
+        let first_visible_index = self.list_state.logical_scroll_top().item_ix;
+        (first_visible_index..self.filtered_indices.len())
+            .take_while(|&filtered_index| {
+                self.list_state
+                    .bounds_for_item(filtered_index)
+                    .is_some_and(|bounds| bounds.bottom() <= viewport.bottom())
+            })
+            .count()
+            .max(1)
+    }
+
+    /// Selects from the first visible line to the last. Lines hidden by the filter in between
+    /// aren't part of the selection, as only visible lines are ever copied.
+    fn select_all_lines(&mut self, cx: &mut Context<Self>) {
+        let (Some(&first_line_index), Some(&last_line_index)) =
+            (self.filtered_indices.first(), self.filtered_indices.last())
+        else {
+            return;
+        };
+        self.selection_anchor = Some(first_line_index);
+        self.selection_head = Some(last_line_index);
+        cx.notify();
+    }
+
+    fn clear_selection(&mut self, cx: &mut Context<Self>) {
+        if self.selection_anchor.is_some() {
+            self.selection_anchor = None;
+            self.selection_head = None;
+            cx.notify();
+        }
+    }
+
+    /// Returns the range of line indices between the selection's anchor and head, inclusive.
+    fn selected_line_range(&self) -> Option<Range<usize>> {
+        let anchor = self.selection_anchor?;
+        let head = self.selection_head?;
+        Some(anchor.min(head)..anchor.max(head) + 1)
+    }
+
+    /// Returns the text of the visible lines within the selection, and how many there are.
+    fn selected_text(&self) -> Option<(String, usize)> {
+        let selection = self.selected_line_range()?;
+        let selected_lines = self
+            .filtered_indices
+            .iter()
+            .filter(|line_index| selection.contains(line_index))
+            .filter_map(|&line_index| self.lines.get(line_index))
+            .map(|line| line.copy_text(self.copy_messages_only))
+            .collect::<Vec<_>>();
+        if selected_lines.is_empty() {
+            return None;
+        }
+        Some((selected_lines.join("\n"), selected_lines.len()))
+    }
+
+    /// Copies `path:line` for the head of the selection, for sharing a line so it can be opened
+    /// again. Entries don't keep their position in the file, so it's found by reading the file, as
+    /// `open_raw_log_file` does.
+    fn copy_file_reference(&mut self, cx: &mut Context<Self>) {
+        let Some(line_index) = self.selection_head else {
+            return;
+        };
+        let Some(line) = self.lines.get(line_index) else {
+            return;
+        };
+        let path = if self
+            .session_start_line()
+            .is_some_and(|session_start_line| line_index < session_start_line)
+        {
+            paths::old_log_file().clone()
+        } else {
+            self.source.path().to_path_buf()
+        };
+        let line_text = line.text.clone();
+        let fs = <dyn Fs>::global(cx);
+        cx.spawn(async move |this, cx| {
+            let content = fs.load_bytes(&path).await?;
+            let row = last_row_with_text(&String::from_utf8_lossy(&content), &line_text);
+            this.update(cx, |this, cx| match row {
+                Some(row) => {
+                    cx.write_to_clipboard(ClipboardItem::new_string(format!(
+                        "{}:{}",
+                        path.display(),
+                        row + 1
+                    )));
+                    this.show_copied_toast("Copied file reference", cx);
+                }
+                None => this.show_copied_toast("The line is no longer in the log file", cx),
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn copy_selection(&mut self, as_markdown: bool, cx: &mut Context<Self>) {
+        let Some((text, line_count)) = self.selected_text() else {
+            return;
+        };
+        self.write_copied_lines(text, line_count, as_markdown, cx);
+    }
+
+    fn toggle_line_collapsed(
+        &mut self,
+        line_index: usize,
+        filtered_index: usize,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.collapsed_lines.remove(&line_index) {
+            self.collapsed_lines.insert(line_index);
+        }
+        self.list_state
+            .remeasure_items(filtered_index..filtered_index + 1);
+        cx.notify();
+    }
+
+    fn toggle_line_pinned(&mut self, line_index: usize, cx: &mut Context<Self>) {
+        if !self.pinned_lines.remove(&line_index) {
+            self.pinned_lines.insert(line_index);
+        }
+        cx.notify();
+    }
+
+    pub fn has_pinned_lines(&self) -> bool {
+        !self.pinned_lines.is_empty()
+    }
+
+    /// Returns the positions in `filtered_indices` of the visible pinned lines, in list order.
+    fn pinned_filtered_indices(&self) -> Vec<usize> {
+        self.filtered_indices
+            .iter()
+            .enumerate()
+            .filter(|(_, line_index)| self.pinned_lines.contains(line_index))
+            .map(|(filtered_index, _)| filtered_index)
+            .collect()
+    }
+
+    pub fn select_next_pinned_line(&mut self, cx: &mut Context<Self>) {
+        let pinned_filtered_indices = self.pinned_filtered_indices();
+        let scroll_top = self.list_state.logical_scroll_top().item_ix;
+        let next = pinned_filtered_indices
+            .iter()
+            .find(|&&filtered_index| filtered_index > scroll_top)
+            .or(pinned_filtered_indices.first());
+        if let Some(&filtered_index) = next {
+            self.scroll_to_pinned_line(filtered_index, cx);
+        }
+    }
+
+    pub fn select_previous_pinned_line(&mut self, cx: &mut Context<Self>) {
+        let pinned_filtered_indices = self.pinned_filtered_indices();
+        let scroll_top = self.list_state.logical_scroll_top().item_ix;
+        let previous = pinned_filtered_indices
+            .iter()
+            .rfind(|&&filtered_index| filtered_index < scroll_top)
+            .or(pinned_filtered_indices.last());
+        if let Some(&filtered_index) = previous {
+            self.scroll_to_pinned_line(filtered_index, cx);
+        }
+    }
+
+    fn scroll_to_pinned_line(&mut self, filtered_index: usize, cx: &mut Context<Self>) {
+        self.set_following(false, cx);
+        self.scroll_to_entry(filtered_index, cx);
+        cx.notify();
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.level_counts.get(LogLevel::Error) > 0
+    }
+
+    /// Returns the positions in `filtered_indices` of the visible error lines, in list order.
+    fn error_filtered_indices(&self) -> Vec<usize> {
+        self.filtered_indices
+            .iter()
+            .enumerate()
+            .filter(|(_, line_index)| {
+                self.lines.get(**line_index).is_some_and(|line| {
+                    parse_log_level(&strip_ansi(&line.text)) == Some(LogLevel::Error)
+                })
+            })
+            .map(|(filtered_index, _)| filtered_index)
+            .collect()
+    }
+
+    pub fn scroll_to_first_error(&mut self, cx: &mut Context<Self>) {
+        // Fuzzy matches are ordered by score, so the earliest error isn't necessarily listed first.
+        let first = self
+            .error_filtered_indices()
+            .into_iter()
+            .min_by_key(|&filtered_index| self.filtered_indices[filtered_index]);
+        if let Some(filtered_index) = first {
+            self.scroll_to_error(filtered_index, cx);
+        }
+    }
+
+    pub fn scroll_to_next_error(&mut self, cx: &mut Context<Self>) {
+        let Some(current_filtered_index) = self
+            .current_error_line
+            .and_then(|line_index| self.filtered_index_of(line_index))
+        else {
+            self.scroll_to_first_error(cx);
+            return;
+        };
+        let error_filtered_indices = self.error_filtered_indices();
+        let next = error_filtered_indices
+            .iter()
+            .find(|&&filtered_index| filtered_index > current_filtered_index)
+            .or(error_filtered_indices.first());
+        if let Some(&filtered_index) = next {
+            self.scroll_to_error(filtered_index, cx);
+        }
+    }
+
+    fn scroll_to_error(&mut self, filtered_index: usize, cx: &mut Context<Self>) {
+        self.current_error_line = Some(self.filtered_indices[filtered_index]);
+        self.set_following(false, cx);
+        self.scroll_to_entry(filtered_index, cx);
+        cx.notify();
+    }
+
+    pub fn toggle_relative_time(&mut self, cx: &mut Context<Self>) {
+        self.show_relative_time = !self.show_relative_time;
+        self._relative_time_task = self.show_relative_time.then(|| {
+            cx.spawn(async move |this, cx| {
+                loop {
+                    cx.background_executor().timer(Duration::from_secs(1)).await;
+                    if this.update(cx, |_, cx| cx.notify()).is_err() {
+                        break;
+                    }
+                }
+            })
+        });
+        cx.notify();
+    }
+
+    pub fn toggle_freeze_relative_time(&mut self, cx: &mut Context<Self>) {
+        self.frozen_now = match self.frozen_now {
+            Some(_) => None,
+            None => Some(Local::now().fixed_offset()),
+        };
+        cx.notify();
+    }
+
+    /// The time entries' relative times are measured from.
+    fn relative_time_now(&self) -> DateTime<FixedOffset> {
+        self.frozen_now
+            .unwrap_or_else(|| Local::now().fixed_offset())
+    }
+
+    pub fn toggle_render_ansi(&mut self, cx: &mut Context<Self>) {
+        self.render_ansi = !self.render_ansi;
+        cx.notify();
+    }
+
+    pub fn toggle_highlight_tokens(&mut self, cx: &mut Context<Self>) {
+        self.highlight_tokens = !self.highlight_tokens;
+        cx.notify();
+    }
+
+    pub fn toggle_tint_rows_by_level(&mut self, cx: &mut Context<Self>) {
+        self.tint_rows_by_level = !self.tint_rows_by_level;
+        cx.notify();
+    }
+
+    fn clear_lines(&mut self, cx: &mut Context<Self>) {
+        self.lines.clear();
+        self.last_viewed_line_count = self.last_viewed_line_count.map(|_| 0);
+        self.new_lines_start = None;
+        self.byte_count = 0;
+        self.level_counts = LevelCounts::default();
+        self.target_counts = TargetCounts::default();
+        self.expanded_lines.clear();
+        self.collapsed_lines.clear();
+        self.pinned_lines.clear();
+        self.json_expanded_lines.clear();
+        self.full_length_lines.clear();
+        self.selection_anchor = None;
+        self.selection_head = None;
+        self.latest_error_line = None;
+        self.current_error_line = None;
+        self.unseen_line_count = 0;
+        self.trimmed_line_count = 0;
+        self.older_matches.clear();
+        self.full_file_search_task = None;
+        self.session_start_entry = None;
+        self.paused_lines.clear();
+        self.filtered_indices.clear();
+        self.level_density = None;
+        self.match_line_indices.clear();
+        self.current_match = None;
+        self.search_bar_active_line = None;
+        self.list_state.reset(0);
+        cx.emit(SearchEvent::MatchesInvalidated);
+        cx.notify();
+    }
+
+    /// Reveals the folder holding the log file, which also has the rotated logs and crash reports.
+    pub fn reveal_log_directory(&mut self, cx: &mut Context<Self>) {
+        let Some(directory) = self.source.path().parent().map(Path::to_path_buf) else {
+            return;
+        };
+        let fs = <dyn Fs>::global(cx);
+        cx.spawn(async move |this, cx| {
+            let exists = fs.is_dir(&directory).await;
+            this.update(cx, |_, cx| {
+                if exists {
+                    cx.reveal_path(&directory);
+                } else {
+                    struct OpenLogRevealError;
+                    cx.emit(OpenLogEvent::ShowToast(Toast::new(
+                        NotificationId::unique::<OpenLogRevealError>(),
+                        format!("Log directory {} doesn't exist", directory.display()),
+                    )));
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn truncate_log_file(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let answer = window.prompt(
+            PromptLevel::Warning,
+            "Truncate the log file?",
+            Some(&format!(
+                "This permanently deletes the contents of {}.",
+                self.source.path().display()
+            )),
+            &["Truncate", "Cancel"],
+            cx,
+        );
+        let fs = <dyn Fs>::global(cx);
+        let path = self.source.path().to_path_buf();
+        cx.spawn(async move |this, cx| {
+            if answer.await != Ok(0) {
+                return;
+            }
+            if let Err(error) = fs.write(&path, &[]).await {
+                this.update(cx, |this, cx| this.show_truncate_error_toast(&error, cx))
+                    .ok();
+            }
+        })
+        .detach();
+    }
+
+    fn show_truncate_error_toast(&self, error: &anyhow::Error, cx: &mut Context<Self>) {
+        struct OpenLogTruncateError;
+        cx.emit(OpenLogEvent::ShowToast(Toast::new(
+            NotificationId::unique::<OpenLogTruncateError>(),
+            format!(
+                "Unable to truncate log file at path {}: {error:#}",
+                self.source.path().display()
+            ),
+        )));
+    }
+
+    fn show_read_error_toast(&self, error: &anyhow::Error, cx: &mut Context<Self>) {
+        struct OpenLogReadError;
+        cx.emit(OpenLogEvent::ShowToast(Toast::new(
+            NotificationId::unique::<OpenLogReadError>(),
+            format!(
+                "Unable to access/open log file at path {}: {error:#}",
+                self.source.path().display()
+            ),
+        )));
+    }
+
+    /// Starts resolving the `path:line` references that haven't been seen before.
+    /// Paths in the project's worktrees resolve immediately, while other absolute paths are
+    /// looked up in the background and shown as links once they're found.
+    fn resolve_file_references(&mut self, references: Vec<FileReference>, cx: &mut Context<Self>) {
+        for reference in references {
+            if self.resolved_paths.contains_key(&reference.path) {
+                continue;
+            }
+            let project = self.project.read(cx);
+            let project_path =
+                project
+                    .find_project_path(&reference.path, cx)
+                    .filter(|project_path| {
+                        project
+                            .entry_for_path(project_path, cx)
+                            .is_some_and(|entry| entry.is_file())
+                    });
+            if let Some(project_path) = project_path {
+                self.resolved_paths.insert(
+                    reference.path,
+                    Some(ResolvedPath::ProjectPath {
+                        project_path,
+                        is_dir: false,
+                    }),
+                );
+                continue;
+            }
+            if !reference.path.starts_with(['/', '~']) && !Path::new(&reference.path).is_absolute()
+            {
+                self.resolved_paths.insert(reference.path, None);
+                continue;
+            }
+
+            let resolve_task = project.resolve_abs_path(&reference.path, cx);
+            self.resolved_paths.insert(reference.path.clone(), None);
+            cx.spawn(async move |this, cx| {
+                let Some(resolved_path) = resolve_task
+                    .await
+                    .filter(|resolved_path| resolved_path.is_file())
+                else {
+                    return;
+                };
+                this.update(cx, |this, cx| {
+                    this.resolved_paths
+                        .insert(reference.path, Some(resolved_path));
+                    cx.notify();
+                })
+                .ok();
+            })
+            .detach();
+        }
+    }
+
+    fn render_entry(
+        &mut self,
+        filtered_index: usize,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let Some(&line_index) = self.filtered_indices.get(filtered_index) else {
+            return Empty.into_any();
+        };
+
+        let Some(line) = self.lines.get(line_index) else {
+            return Empty.into_any();
+        };
+        let collapsed = self.collapsed_lines.contains(&line_index);
+        let unresolved_references = iter::once(&line.file_references)
+            .chain(
+                line.continuation_file_references
+                    .iter()
+                    .filter(|_| !collapsed),
+            )
+            .flatten()
+            .filter(|(_, reference)| !self.resolved_paths.contains_key(&reference.path))
+            .map(|(_, reference)| reference.clone())
+            .collect::<Vec<_>>();
+        if !unresolved_references.is_empty() {
+            self.resolve_file_references(unresolved_references, cx);
+        }
+        let Some(line) = self.lines.get(line_index) else {
+            return Empty.into_any();
+        };
+
+        let base_size = self.text_size(cx);
+        let colors = cx.theme().colors();
+        let border_color = colors.border;
+        let current_match_background = colors.search_active_match_background;
+        let pinned_background = colors.element_selected;
+        let is_current_match = self.current_match == Some(line_index)
+            || self.search_bar_active_line == Some(line_index);
+        let is_pinned = self.pinned_lines.contains(&line_index);
+        let is_context_line = self.is_filter_context_line(line_index);
+        let is_new = self
+            .new_lines_start
+            .is_some_and(|new_lines_start| line_index >= new_lines_start);
+        let new_line_marker_color = colors.text_accent;
+        let is_selected = self
+            .selected_line_range()
+            .is_some_and(|selection| selection.contains(&line_index));
+        let selection_background = cx.theme().players().local().selection;
+        let level = parse_log_level(&strip_ansi(&line.text));
+        let color = level.map_or(Color::Default, |level| level.color(cx));
+        let level_background = match level {
+            Some(LogLevel::Error) if self.tint_rows_by_level => {
+                Some(cx.theme().status().error_background.opacity(0.5))
+            }
+            Some(LogLevel::Warn) if self.tint_rows_by_level => {
+                Some(cx.theme().status().warning_background.opacity(0.5))
+            }
+            _ => None,
+        };
+        let line_number_width = self.lines.len().to_string().len();
+        let relative_time = self.show_relative_time.then(|| {
+            parse_timestamp(&strip_ansi(&line.text))
+                .map(|timestamp| format_relative_time(self.relative_time_now() - timestamp))
+        });
+        // Compact entries stay on one line even when wrapping, unless expanded.
+        let truncated =
+            (!self.wrap_lines || self.compact) && !self.expanded_lines.contains(&line_index);
+        let continuation_line_count = line.continuation_lines.len();
+        let has_invalid_utf8 = iter::once(&line.text)
+            .chain(&line.continuation_lines)
+            .any(|text| text.contains(char::REPLACEMENT_CHARACTER));
+        let plain_text = strip_ansi(&line.text);
+        let json_payload = try_extract_json(&plain_text);
+        let has_json_payload = json_payload.is_some();
+        let target = parse_log_target(&plain_text).map(SharedString::new);
+        let json_expanded = self.json_expanded_lines.contains(&line_index);
+        // Giant lines are slow to lay out, so only their start is rendered until asked for.
+        let max_line_length = LogViewSettings::get_global(cx).max_line_length;
+        let hidden_char_count: usize = iter::once(&line.text)
+            .chain(&line.continuation_lines)
+            .map(|text| chars_past_limit(&strip_ansi(text), max_line_length))
+            .sum();
+        let full_length = self.full_length_lines.contains(&line_index);
+        let char_limit = (!full_length).then_some(max_line_length);
+
+        let text = v_flex()
+            .flex_1()
+            .min_w_0()
+            .map(|this| match json_payload.filter(|_| json_expanded) {
+                Some((prefix, json)) => this
+                    .child(self.render_line_text(
+                        0,
+                        &SharedString::from(prefix.to_string()),
+                        None,
+                        &line.file_references,
+                        color,
+                        truncated,
+                        char_limit,
+                        cx,
+                    ))
+                    .child(render_json(&json, base_size, cx)),
+                None => this.child(self.render_line_text(
+                    0,
+                    &line.text,
+                    self.fuzzy_match_ranges.get(&line_index),
+                    &line.file_references,
+                    color,
+                    truncated,
+                    char_limit,
+                    cx,
+                )),
+            })
+            .when(continuation_line_count > 0, |this| {
+                if collapsed {
+                    this.child(
+                        Label::new(if continuation_line_count == 1 {
+                            "1 more line".to_string()
+                        } else {
+                            format!("{continuation_line_count} more lines")
+                        })
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    )
+                } else {
+                    this.children(
+                        line.continuation_lines
+                            .iter()
+                            .zip(&line.continuation_file_references)
+                            .enumerate()
+                            .map(
+                                |(continuation_index, (continuation_line, file_references))| {
+                                    self.render_line_text(
+                                        continuation_index + 1,
+                                        continuation_line,
+                                        None,
+                                        file_references,
+                                        color,
+                                        truncated,
+                                        char_limit,
+                                        cx,
+                                    )
+                                },
+                            ),
+                    )
+                }
+            })
+            .when(hidden_char_count > 0, |this| {
+                this.child(
+                    Button::new(
+                        ("log-entry-full-length", line_index),
+                        if full_length {
+                            "Show Less".to_string()
+                        } else {
+                            format!("… Show Full ({hidden_char_count} more characters)")
+                        },
+                    )
+                    .label_size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .on_click(cx.listener(move |this, _, _window, cx| {
+                        cx.stop_propagation();
+                        this.toggle_full_length(line_index, filtered_index, cx);
+                    })),
+                )
+            });
+
+        let show_full_line_tooltip = truncated || hidden_char_count > 0;
+        let full_line_text = line.text.clone();
+
+        let entry = h_flex()
+            .id(filtered_index)
+            .group("log-entry")
+            .w_full()
+            .when(show_full_line_tooltip, |this| {
+                this.tooltip(move |_window, cx| {
+                    Tooltip::simple(
+                        util::truncate_and_trailoff(
+                            &strip_ansi(&full_line_text),
+                            LINE_TOOLTIP_MAX_CHARS,
+                        ),
+                        cx,
+                    )
+                })
+            })
+            .map(|this| {
+                if self.compact {
+                    this.py_px().pl_2().pr_3()
+                } else {
+                    this.py_2().pl_4().pr_5().border_b_1()
+                }
+            })
+            .gap_1()
+            .items_start()
+            .border_color(border_color)
+            .when_some(level_background, |this, background| this.bg(background))
+            .when(is_pinned, |this| this.bg(pinned_background))
+            .when(is_selected, |this| this.bg(selection_background))
+            .when(is_current_match, |this| this.bg(current_match_background))
+            .when(is_context_line, |this| this.opacity(0.6))
+            .when(is_new, |this| {
+                this.relative().child(
+                    div()
+                        .absolute()
+                        .left_0()
+                        .top_0()
+                        .bottom_0()
+                        .w_0p5()
+                        .bg(new_line_marker_color),
+                )
+            })
+            .cursor_pointer()
+            .on_click(cx.listener(move |this, event: &ClickEvent, _window, cx| {
+                cx.stop_propagation();
+                // Double-clicking expands a truncated line, as a single click selects it.
+                if event.click_count() == 2 && (!this.wrap_lines || this.compact) {
+                    this.toggle_line_expanded(line_index, filtered_index, cx);
+                } else {
+                    this.click_line(line_index, event.modifiers().shift, cx);
+                }
+            }))
+            .child(
+                div()
+                    .flex_none()
+                    .w_4()
+                    .when(continuation_line_count > 0, |this| {
+                        this.child(
+                            Disclosure::new(("log-entry-disclosure", line_index), !collapsed)
+                                .on_click(cx.listener(move |this, _, _window, cx| {
+                                    this.toggle_line_collapsed(line_index, filtered_index, cx);
+                                })),
+                        )
+                    }),
+            )
+            .when(self.show_line_numbers, |this| {
+                this.child(
+                    Label::new(format!("{:>line_number_width$}", line_index + 1))
+                        .buffer_font(cx)
+                        .size(LabelSize::Custom(base_size))
+                        .color(Color::Muted)
+                        .flex_none(),
+                )
+            })
+            .when_some(relative_time, |this, relative_time| {
+                this.child(div().flex_none().w(rems(3.)).children(relative_time.map(
+                    |relative_time| {
+                        Label::new(relative_time)
+                            .buffer_font(cx)
+                            .size(LabelSize::Custom(base_size))
+                            .color(Color::Muted)
+                    },
+                )))
+            })
+            .when(has_invalid_utf8, |this| {
+                this.child(
+                    div()
+                        .id(("invalid-utf8", line_index))
+                        .flex_none()
+                        .child(
+                            Icon::new(IconName::Warning)
+                                .size(IconSize::XSmall)
+                                .color(Color::Muted),
+                        )
+                        .tooltip(Tooltip::text("Contains bytes that aren't valid UTF-8")),
+                )
+            })
+            .child(text)
+            .child(
+                h_flex()
+                    .flex_none()
+                    .when_some(target, |this, target| {
+                        this.child(
+                            div().visible_on_hover("log-entry").child(
+                                Button::new(("mute_target", line_index), target.clone())
+                                    .label_size(LabelSize::XSmall)
+                                    .color(Color::Muted)
+                                    .start_icon(
+                                        Icon::new(IconName::EyeOff)
+                                            .size(IconSize::XSmall)
+                                            .color(Color::Muted),
+                                    )
+                                    .tooltip(Tooltip::text("Hide Lines from This Target"))
+                                    .on_click(cx.listener(move |this, _, _window, cx| {
+                                        this.toggle_target_muted(&target, cx);
+                                    })),
+                            ),
+                        )
+                    })
+                    .child(
+                        div()
+                            .when(!is_pinned, |this| this.visible_on_hover("log-entry"))
+                            .child(
+                                IconButton::new(
+                                    ("pin_line", line_index),
+                                    if is_pinned {
+                                        IconName::StarFilled
+                                    } else {
+                                        IconName::Star
+                                    },
+                                )
+                                .icon_size(IconSize::Small)
+                                .toggle_state(is_pinned)
+                                .tooltip(Tooltip::text(if is_pinned {
+                                    "Unpin Line"
+                                } else {
+                                    "Pin Line"
+                                }))
+                                .on_click(cx.listener(
+                                    move |this, _, _window, cx| {
+                                        this.toggle_line_pinned(line_index, cx);
+                                    },
+                                )),
+                            ),
+                    )
+                    .when(has_json_payload, |this| {
+                        this.child(
+                            div()
+                                .when(!json_expanded, |this| this.visible_on_hover("log-entry"))
+                                .child(
+                                    IconButton::new(("toggle_json", line_index), IconName::Json)
+                                        .icon_size(IconSize::Small)
+                                        .toggle_state(json_expanded)
+                                        .tooltip(Tooltip::text(if json_expanded {
+                                            "Show Raw Line"
+                                        } else {
+                                            "Pretty-Print JSON"
+                                        }))
+                                        .on_click(cx.listener(move |this, _, _window, cx| {
+                                            this.toggle_json_expanded(
+                                                line_index,
+                                                filtered_index,
+                                                cx,
+                                            );
+                                        })),
+                                ),
+                        )
+                    })
+                    .child(
+                        div().visible_on_hover("log-entry").child(
+                            IconButton::new(("copy_line", line_index), IconName::Copy)
+                                .icon_size(IconSize::Small)
+                                .tooltip(Tooltip::text("Copy Line"))
+                                .on_click(cx.listener(move |this, _, _window, cx| {
+                                    this.copy_line(line_index, cx);
+                                })),
+                        ),
+                    ),
+            );
+        let is_fuzzy_query = self.is_fuzzy_query();
+        let show_trimmed_lines_header =
+            filtered_index == 0 && self.trimmed_line_count > 0 && !is_fuzzy_query;
+        // The separator goes above the first visible entry of the session, even when the filter
+        // hides the one that actually starts it.
+        let show_session_separator = !is_fuzzy_query
+            && self.session_start_line().is_some_and(|session_start| {
+                line_index >= session_start
+                    && filtered_index
+                        .checked_sub(1)
+                        .and_then(|previous_index| self.filtered_indices.get(previous_index))
+                        .is_some_and(|&previous_line_index| previous_line_index < session_start)
+            });
+        let time_gap = LogViewSettings::get_global(cx)
+            .time_gap_threshold
+            .filter(|_| !is_fuzzy_query)
+            .and_then(|threshold| {
+                let previous_line = filtered_index
+                    .checked_sub(1)
+                    .and_then(|previous_index| self.filtered_indices.get(previous_index))
+                    .and_then(|&previous_line_index| self.lines.get(previous_line_index))?;
+                let line = self.lines.get(line_index)?;
+                time_gap(&previous_line.text, &line.text, threshold)
+            });
+        if !show_trimmed_lines_header && !show_session_separator && time_gap.is_none() {
+            return entry.into_any();
+        }
+        v_flex()
+            .when(show_trimmed_lines_header, |this| {
+                this.child(self.render_trimmed_lines_header(cx))
+            })
+            .when(show_session_separator, |this| {
+                this.child(render_session_separator(cx))
+            })
+            .when_some(time_gap, |this, time_gap| {
+                this.child(render_time_gap_separator(time_gap, cx))
+            })
+            .child(entry)
+            .into_any()
+    }
+
+    fn render_trimmed_lines_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let trimmed_line_count = self.trimmed_line_count;
+        h_flex()
+            .w_full()
+            .py_1()
+            .pl_4()
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                Label::new(if trimmed_line_count == 1 {
+                    "⋯ 1 older entry trimmed".to_string()
+                } else {
+                    format!("⋯ {trimmed_line_count} older entries trimmed")
+                })
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+            )
+            .child(
+                Button::new("open-raw-log-file", "Open Raw Log File")
+                    .label_size(LabelSize::Small)
+                    .tooltip(Tooltip::text(match self.max_bytes {
+                        Some(max_bytes) => format!(
+                            "Only the last {} entries, up to {}, are kept in the view",
+                            self.max_lines,
+                            util::size::format_file_size(max_bytes as u64, true)
+                        ),
+                        None => format!(
+                            "Only the last {} entries are kept in the view",
+                            self.max_lines
+                        ),
+                    }))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        open_raw_log_file(this.source.path().to_path_buf(), None, window, cx);
+                    })),
+            )
+    }
+
+    /// Renders one line of an entry. `text_index` tells the entry's header (0) and continuation
+    /// lines apart, so their clickable file references get distinct element IDs.
+    /// `file_references` are the references found in the line's text when it was added.
+    fn render_line_text(
+        &self,
+        text_index: usize,
+        text: &SharedString,
+        fuzzy_match_ranges: Option<&Vec<Range<usize>>>,
+        file_references: &[(Range<usize>, FileReference)],
+        color: Color,
+        truncated: bool,
+        char_limit: Option<usize>,
+        cx: &App,
+    ) -> impl IntoElement {
+        let base_size = self.text_size(cx);
+        let match_background = cx.theme().colors().search_match_background;
+        let (display_text, mut ansi_styles) = parse_ansi(text);
+        let mut display_text = match display_text {
+            Cow::Borrowed(_) => text.clone(),
+            Cow::Owned(display_text) => SharedString::from(display_text),
+        };
+        // Subsystems can register their own colorizers with `zlog` for the lines they write.
+        let mut colorizer_runs = zlog::colorize::colorize(&display_text).map(|runs| {
+            runs.into_iter()
+                .map(|run| (run.range, run.style))
+                .collect::<Vec<_>>()
+        });
+        let display_len = char_limit
+            .and_then(|char_limit| display_text.char_indices().nth(char_limit))
+            .map(|(byte_index, _)| byte_index);
+        if let Some(display_len) = display_len {
+            display_text = SharedString::from(display_text[..display_len].to_string());
+            ansi_styles = clip_ranges(ansi_styles, display_len);
+            colorizer_runs = colorizer_runs.map(|runs| clip_ranges(runs, display_len));
+        }
+        let ansi_highlights: Vec<_> = if self.render_ansi {
+            ansi_styles
+                .into_iter()
+                .map(|(range, style)| (range, style.highlight(cx)))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let token_highlights: Vec<_> = if let Some(colorizer_runs) = colorizer_runs {
+            let syntax = cx.theme().syntax();
+            valid_styled_runs(colorizer_runs, &display_text)
+                .into_iter()
+                .filter_map(|(range, style)| Some((range, syntax.style_for_name(style)?)))
+                .collect()
+        } else if self.highlight_tokens {
+            let syntax = cx.theme().syntax();
+            log_tokens(&display_text)
+                .into_iter()
+                .filter_map(|(range, token)| {
+                    Some((range, syntax.style_for_name(token.syntax_name())?))
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let match_ranges = match fuzzy_match_ranges {
+            Some(fuzzy_match_ranges) => match display_len {
+                Some(display_len) => fuzzy_match_ranges
+                    .iter()
+                    .filter(|range| range.start < display_len)
+                    .map(|range| range.start..range.end.min(display_len))
+                    .collect(),
+                None => fuzzy_match_ranges.clone(),
+            },
+            None => self.match_ranges(&display_text),
+        };
+        let (link_ranges, links): (Vec<_>, Vec<_>) = file_references
+            .iter()
+            .filter(|(range, _)| range.end <= display_text.len())
+            .filter_map(|(range, reference)| {
+                let resolved_path = self.resolved_paths.get(&reference.path)?.clone()?;
+                Some((range.clone(), (resolved_path, reference.clone())))
+            })
+            .unzip();
+        let link_highlight = HighlightStyle {
+            underline: Some(UnderlineStyle {
+                thickness: px(1.),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        div()
+            .w_full()
+            .when(truncated, |this| {
+                this.overflow_hidden().whitespace_nowrap().text_ellipsis()
+            })
+            .map(|this| {
+                if match_ranges.is_empty()
+                    && ansi_highlights.is_empty()
+                    && token_highlights.is_empty()
+                    && link_ranges.is_empty()
+                {
+                    this.child(
+                        Label::new(display_text)
+                            .buffer_font(cx)
+                            .size(LabelSize::Custom(base_size))
+                            .color(color),
+                    )
+                } else {
+                    let highlight = HighlightStyle {
+                        background_color: Some(match_background),
+                        ..Default::default()
+                    };
+                    let styled_text =
+                        StyledText::new(display_text).with_highlights(combine_highlights(
+                            combine_highlights(
+                                combine_highlights(token_highlights, ansi_highlights),
+                                link_ranges
+                                    .iter()
+                                    .map(|range| (range.clone(), link_highlight)),
+                            ),
+                            match_ranges.into_iter().map(|range| (range, highlight)),
+                        ));
+                    this.font_buffer(cx)
+                        .text_size(base_size)
+                        .text_color(color.color(cx))
+                        .map(|this| {
+                            if links.is_empty() {
+                                this.child(styled_text)
+                            } else {
+                                this.child(
+                                    InteractiveText::new(
+                                        ("log-line-text", text_index),
+                                        styled_text,
+                                    )
+                                    .on_click(
+                                        link_ranges,
+                                        move |link_index, window, cx| {
+                                            if let Some((resolved_path, reference)) =
+                                                links.get(link_index)
+                                            {
+                                                open_file_reference(
+                                                    resolved_path.clone(),
+                                                    reference,
+                                                    window,
+                                                    cx,
+                                                );
+                                            }
+                                        },
+                                    ),
+                                )
+                            }
+                        })
+                }
+            })
+    }
+
+    /// Splits `filtered_indices` into up to `DENSITY_BAND_COUNT` bands and counts the errors and
+    /// warnings in each.
+    fn compute_level_density(&self) -> Vec<LevelDensity> {
+        let item_count = self.filtered_indices.len();
+        let band_count = DENSITY_BAND_COUNT.min(item_count);
+        (0..band_count)
+            .map(|band| {
+                let items = band * item_count / band_count..(band + 1) * item_count / band_count;
+                let mut density = LevelDensity {
+                    first_item_ix: items.start,
+                    item_count: items.len(),
+                    error_count: 0,
+                    warning_count: 0,
+                };
+                for &line_index in &self.filtered_indices[items] {
+                    let level = self
+                        .lines
+                        .get(line_index)
+                        .and_then(|line| parse_log_level(&strip_ansi(&line.text)));
+                    match level {
+                        Some(LogLevel::Error) => density.error_count += 1,
+                        Some(LogLevel::Warn) => density.warning_count += 1,
+                        _ => {}
+                    }
+                }
+                density
+            })
+            .collect()
+    }
+
+    /// Renders a strip beside the list coloring each band by its share of errors and warnings.
+    /// Clicking a band scrolls to its first line.
+    fn render_level_density(&mut self, cx: &mut Context<Self>) -> impl IntoElement {
+        let level_density = match &self.level_density {
+            Some(level_density) => level_density.clone(),
+            None => {
+                let level_density = self.compute_level_density();
+                self.level_density = Some(level_density.clone());
+                level_density
+            }
+        };
+        let error_color = Color::Error.color(cx);
+        let warning_color = Color::Warning.color(cx);
+
+        v_flex()
+            .id("log-level-density")
+            .flex_none()
+            .w_1p5()
+            .h_full()
+            .children(
+                level_density
+                    .into_iter()
+                    .enumerate()
+                    .map(|(band, density)| {
+                        let (color, count) = if density.error_count > 0 {
+                            (error_color, density.error_count)
+                        } else {
+                            (warning_color, density.warning_count)
+                        };
+                        let share = count as f32 / density.item_count.max(1) as f32;
+                        let first_item_ix = density.first_item_ix;
+                        div()
+                            .id(("log-level-density-band", band))
+                            .flex_1()
+                            .w_full()
+                            .when(count > 0, |this| this.bg(color.opacity(0.3 + 0.7 * share)))
+                            .cursor_pointer()
+                            .on_click(cx.listener(move |this, _, _window, cx| {
+                                cx.stop_propagation();
+                                this.set_following(false, cx);
+                                this.scroll_to_entry(first_item_ix, cx);
+                                cx.notify();
+                            }))
+                    }),
+            )
+    }
+
+    /// Renders a chip per prominent level with its line count, each filtering to that level.
+    fn render_level_summary(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .flex_none()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .children(
+                [LogLevel::Error, LogLevel::Warn, LogLevel::Info].map(|level| {
+                    Button::new(
+                        ("log-level-summary", level as usize),
+                        format!("{} {}", self.level_counts.get(level), level.label()),
+                    )
+                    .label_size(LabelSize::Small)
+                    .color(level.color(cx))
+                    .toggle_state(self.filter.level_filter == LevelMask::only(level))
+                    .tooltip(Tooltip::text(format!("Show Only {} Lines", level.label())))
+                    .on_click(cx.listener(move |this, _, _window, cx| {
+                        this.toggle_level_only(level, cx);
+                    }))
+                }),
+            )
+    }
+
+    /// Renders the latest error as a clickable row, if it's visible in the list and the viewport
+    /// is scrolled away from the tail.
+    fn render_latest_error(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        if self.viewing_tail {
+            return None;
+        }
+        let line_index = self.latest_error_line?;
+        let filtered_index = self.filtered_index_of(line_index)?;
+        let line = self.lines.get(line_index)?;
+
+        Some(
+            h_flex()
+                .id("latest-error")
+                .flex_none()
+                .px_2()
+                .py_1()
+                .gap_2()
+                .border_b_1()
+                .border_color(cx.theme().colors().border)
+                .bg(cx.theme().status().error_background)
+                .cursor_pointer()
+                .tooltip(Tooltip::text("Jump to Latest Error"))
+                .on_click(cx.listener(move |this, _, _window, cx| {
+                    this.reveal_entry(filtered_index, cx);
+                    cx.notify();
+                }))
+                .child(
+                    Icon::new(IconName::XCircle)
+                        .size(IconSize::Small)
+                        .color(Color::Error),
+                )
+                .child(
+                    div()
+                        .flex_1()
+                        .min_w_0()
+                        .overflow_hidden()
+                        .whitespace_nowrap()
+                        .text_ellipsis()
+                        .child(
+                            Label::new(SharedString::from(strip_ansi(&line.text).into_owned()))
+                                .buffer_font(cx)
+                                .size(LabelSize::Small)
+                                .color(Color::Error),
+                        ),
+                ),
+        )
+    }
+
+    /// Renders the trimmed entries found by the full file search above the list. Clicking one
+    /// opens the log file it came from at that line.
+    fn render_older_matches(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let is_searching = self.full_file_search_task.is_some();
+        if !self.search_full_file || (!is_searching && self.older_matches.is_empty()) {
+            return None;
+        }
+
+        let match_count = self.older_matches.len();
+        let summary = match (match_count, is_searching) {
+            (0, _) => "Searching trimmed entries on disk…".to_string(),
+            (1, true) => "1 older match on disk so far…".to_string(),
+            (1, false) => "1 older match on disk".to_string(),
+            (_, true) => format!("{match_count} older matches on disk so far…"),
+            (_, false) => format!("{match_count} older matches on disk"),
+        };
+        Some(
+            v_flex()
+                .flex_none()
+                .border_b_1()
+                .border_color(cx.theme().colors().border)
+                .child(
+                    h_flex()
+                        .px_2()
+                        .py_1()
+                        .gap_2()
+                        .when(is_searching, |this| {
+                            this.child(
+                                Icon::new(IconName::ArrowCircle)
+                                    .size(IconSize::Small)
+                                    .color(Color::Muted)
+                                    .with_rotate_animation(2),
+                            )
+                        })
+                        .child(
+                            Label::new(summary)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .when(is_searching, |this| {
+                            this.child(
+                                Button::new("cancel-full-file-search", "Stop")
+                                    .label_size(LabelSize::Small)
+                                    .on_click(cx.listener(|this, _, _window, cx| {
+                                        this.cancel_full_file_search(cx);
+                                    })),
+                            )
+                        }),
+                )
+                .when(match_count > 0, |this| {
+                    this.child(
+                        v_flex()
+                            .id("older-matches")
+                            .max_h_32()
+                            .overflow_y_scroll()
+                            .children(self.older_matches.iter().enumerate().map(
+                                |(ix, older_match)| {
+                                    let text = strip_ansi(&older_match.line.text).into_owned();
+                                    let color = match parse_log_level(&text) {
+                                        Some(level) => level.color(cx),
+                                        None => Color::Default,
+                                    };
+                                    let path = if older_match.in_old_log {
+                                        paths::old_log_file().clone()
+                                    } else {
+                                        self.source.path().to_path_buf()
+                                    };
+                                    let line_text = older_match.line.text.clone();
+                                    div()
+                                        .id(("older-match", ix))
+                                        .px_2()
+                                        .overflow_hidden()
+                                        .whitespace_nowrap()
+                                        .text_ellipsis()
+                                        .cursor_pointer()
+                                        .hover(|style| style.bg(cx.theme().colors().element_hover))
+                                        .on_click(move |_, window, cx| {
+                                            open_raw_log_file(
+                                                path.clone(),
+                                                Some(line_text.clone()),
+                                                window,
+                                                cx,
+                                            );
+                                        })
+                                        .child(
+                                            Label::new(text)
+                                                .buffer_font(cx)
+                                                .size(LabelSize::Small)
+                                                .color(color),
+                                        )
+                                },
+                            )),
+                    )
+                }),
+        )
+    }
+
+    /// Renders the unfiltered lines around the selected line, or the current match when nothing
+    /// is selected, so the lead-up to a filtered line can be read without clearing the filter.
+    fn render_context_pane(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        if !self.show_context_pane {
+            return None;
+        }
+
+        let pane = v_flex()
+            .id("log-context")
+            .flex_none()
+            .h(rems(12.))
+            .px_2()
+            .py_1()
+            .border_t_1()
+            .border_color(cx.theme().colors().border)
+            .bg(cx.theme().colors().panel_background)
+            .overflow_y_scroll();
+        let Some(line_index) = self
+            .selection_head
+            .or(self.current_match)
+            .filter(|&line_index| line_index < self.lines.len())
+        else {
+            return Some(
+                pane.justify_center().items_center().child(
+                    Label::new("Select a line to see the lines around it")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+            );
+        };
+
+        let context_line_count = LogViewSettings::get_global(cx).context_line_count;
+        let context = line_index.saturating_sub(context_line_count)
+            ..(line_index + context_line_count + 1).min(self.lines.len());
+        let base_size = self.text_size(cx);
+        let line_number_width = context.end.to_string().len();
+        let selected_background = cx.theme().colors().element_selected;
+        Some(pane.children(context.filter_map(|context_line_index| {
+            let line = self.lines.get(context_line_index)?;
+            let text = strip_ansi(&line.text);
+            // Lines hidden by the filter are dimmed so the filtered ones stand out.
+            let color = if self.filtered_index_of(context_line_index).is_some() {
+                parse_log_level(&text).map_or(Color::Default, |level| level.color(cx))
+            } else {
+                Color::Muted
+            };
+            Some(
+                h_flex()
+                    .gap_2()
+                    .when(context_line_index == line_index, |this| {
+                        this.bg(selected_background)
+                    })
+                    .child(
+                        Label::new(format!("{:>line_number_width$}", context_line_index + 1))
+                            .buffer_font(cx)
+                            .size(LabelSize::Custom(base_size))
+                            .color(Color::Muted)
+                            .flex_none(),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .overflow_hidden()
+                            .whitespace_nowrap()
+                            .text_ellipsis()
+                            .child(
+                                Label::new(SharedString::from(text.into_owned()))
+                                    .buffer_font(cx)
+                                    .size(LabelSize::Custom(base_size))
+                                    .color(color),
+                            ),
+                    ),
+            )
+        })))
+    }
+
+    /// Renders a button floating over the bottom of the list while scrolled away from the tail,
+    /// counting the lines logged since, that scrolls back down and resumes following.
+    fn render_new_lines_button(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        let new_line_count = self.unseen_line_count + self.paused_lines.len();
+        if self.viewing_tail || new_line_count == 0 {
+            return None;
+        }
+
+        Some(
+            div().absolute().bottom_2().right_4().child(
+                Button::new("jump-to-new-lines", format!("{new_line_count} new"))
+                    .style(ButtonStyle::Filled)
+                    .label_size(LabelSize::Small)
+                    .start_icon(
+                        Icon::new(IconName::ArrowDown)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .tooltip(Tooltip::text("Jump to Latest"))
+                    .on_click(cx.listener(|this, _, _window, cx| this.scroll_to_bottom(cx))),
+            ),
+        )
+    }
+
+    /// Explains why no entries are shown, with buttons for getting some: clearing the filter
+    /// when it hides them all, or looking at the file itself when there aren't any.
+    fn render_empty_state(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let message = if self.lines.is_empty() {
+            "No log entries recorded yet"
+        } else if self.filter.level_filter != LevelMask::ALL && !self.has_non_level_filters() {
+            "No entries match the current level filter"
+        } else {
+            "No entries match the current filter"
+        };
+        let actions = if self.lines.is_empty() {
+            h_flex()
+                .gap_1()
+                .child(
+                    Button::new("empty-open-raw-log-file", "Open Raw Log File")
+                        .style(ButtonStyle::Outlined)
+                        .label_size(LabelSize::Small)
+                        .start_icon(
+                            Icon::new(IconName::File)
+                                .size(IconSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            open_raw_log_file(this.source.path().to_path_buf(), None, window, cx);
+                        })),
+                )
+                .child(
+                    Button::new("empty-reload-log", "Reload")
+                        .style(ButtonStyle::Outlined)
+                        .label_size(LabelSize::Small)
+                        .start_icon(
+                            Icon::new(IconName::RotateCw)
+                                .size(IconSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .on_click(cx.listener(|this, _, _window, cx| this.reload(cx))),
+                )
+        } else {
+            h_flex().child(
+                Button::new("empty-clear-filter", "Clear Filter")
+                    .style(ButtonStyle::Outlined)
+                    .label_size(LabelSize::Small)
+                    .start_icon(
+                        Icon::new(IconName::Close)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .on_click(cx.listener(|this, _, _window, cx| this.clear_filters(cx))),
+            )
+        };
+        v_flex()
+            .size_full()
+            .gap_2()
+            .justify_center()
+            .items_center()
+            .child(Label::new(message).color(Color::Muted))
+            .child(actions)
+    }
+
+    fn copy_line(&mut self, line_index: usize, cx: &mut Context<Self>) {
+        let Some(line) = self.lines.get(line_index) else {
+            return;
+        };
+        cx.write_to_clipboard(ClipboardItem::new_string(
+            line.copy_text(self.copy_messages_only),
+        ));
+        self.show_copied_toast("Copied", cx);
+    }
+
+    /// The shown entries as they'd be copied, one after another. This is the text the search bar
+    /// searches.
+    pub fn filtered_text(&self) -> String {
+        self.filtered_indices
+            .iter()
+            .filter_map(|&line_index| self.lines.get(line_index))
+            .map(|line| line.copy_text(self.copy_messages_only))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn is_copying_messages_only(&self) -> bool {
+        self.copy_messages_only
+    }
+
+    pub fn toggle_copy_messages_only(&mut self, cx: &mut Context<Self>) {
+        self.copy_messages_only = !self.copy_messages_only;
+        cx.emit(SearchEvent::MatchesInvalidated);
+        cx.notify();
+    }
+
+    fn copy_filtered_lines(&mut self, as_markdown: bool, cx: &mut Context<Self>) {
+        if self.filtered_indices.is_empty() {
+            return;
+        }
+        self.write_copied_lines(
+            self.filtered_text(),
+            self.filtered_indices.len(),
+            as_markdown,
+            cx,
+        );
+    }
+
+    /// The selected entries, or all shown ones without a selection, fenced as a Markdown code
+    /// block for an agent thread. Past `AGENT_CONTEXT_MAX_BYTES`, the earliest entries are left
+    /// out with a note saying so, as the latest are usually the ones being asked about.
+    fn agent_context(&self, cx: &App) -> Option<String> {
+        let selection = self.selected_line_range();
+        let entries = self
+            .filtered_indices
+            .iter()
+            .filter(|line_index| {
+                selection
+                    .as_ref()
+                    .is_none_or(|selection| selection.contains(line_index))
+            })
+            .filter_map(|&line_index| self.lines.get(line_index))
+            .map(|line| line.copy_text(self.copy_messages_only))
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            return None;
+        }
+        let kept_count = trailing_entry_count_within(&entries, AGENT_CONTEXT_MAX_BYTES);
+        let omitted_count = entries.len() - kept_count;
+        let code_block = markdown_code_block(
+            &entries[omitted_count..].join("\n"),
+            &LogViewSettings::get_global(cx).code_block_language,
+        );
+        Some(if omitted_count > 0 {
+            format!(
+                "The first {omitted_count} of {} log entries were left out for length.\n\n{code_block}\n",
+                entries.len()
+            )
+        } else {
+            format!("{code_block}\n")
+        })
+    }
+
+    /// Copies `text` to the clipboard, optionally fenced as a Markdown code block for pasting into
+    /// issues, and reports how many lines were copied.
+    fn write_copied_lines(
+        &mut self,
+        text: String,
+        line_count: usize,
+        as_markdown: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let text = if as_markdown {
+            markdown_code_block(&text, &LogViewSettings::get_global(cx).code_block_language)
+        } else {
+            text
+        };
+        cx.write_to_clipboard(ClipboardItem::new_string(text));
+        let message = if line_count == 1 {
+            "Copied 1 line".to_string()
+        } else {
+            format!("Copied {line_count} lines")
+        };
+        self.show_copied_toast(message, cx);
+    }
+
+    fn show_copied_toast(&self, message: impl Into<Cow<'static, str>>, cx: &mut Context<Self>) {
+        struct OpenLogCopied;
+        cx.emit(OpenLogEvent::ShowToast(
+            Toast::new(NotificationId::unique::<OpenLogCopied>(), message).autohide(),
+        ));
+    }
+}
+
+/// How much was read when the log files were last fully loaded, and how long it took.
+#[derive(Clone, Copy, Debug)]
+pub struct LogLoadStats {
+    pub byte_len: u64,
+    pub load_time: Duration,
+}
+
+/// An entry trimmed from the view that the full file search found to match the query.
+struct OlderMatch {
+    /// Whether the entry was read from `old_log_file()` rather than the viewed log file.
+    in_old_log: bool,
+    line: LogLine,
+}
+
+/// A log line together with the continuation lines (stack frames, wrapped messages) that
+/// followed it in the file.
+struct LogLine {
+    text: SharedString,
+    continuation_lines: Vec<SharedString>,
+    /// `text` and `continuation_lines` with escape sequences removed and lowercased, which
+    /// queries are matched against. Keeping them saves lowercasing every line on each keystroke.
+    lowercase_text: String,
+    lowercase_continuation_lines: Vec<String>,
+    /// The `path:line` references in `text` and each continuation line, found once when the
+    /// line is added rather than on every render.
+    file_references: Vec<(Range<usize>, FileReference)>,
+    continuation_file_references: Vec<Vec<(Range<usize>, FileReference)>>,
+}
+
+impl LogLine {
+    fn new(text: &str) -> Self {
+        let plain_text = strip_ansi(text);
+        Self {
+            text: SharedString::from(text.to_string()),
+            continuation_lines: Vec::new(),
+            lowercase_text: plain_text.to_lowercase(),
+            lowercase_continuation_lines: Vec::new(),
+            file_references: file_references(&plain_text),
+            continuation_file_references: Vec::new(),
+        }
+    }
+
+    /// Makes an entry of a record split off by a `RecordSeparator`, whose first line is the
+    /// entry's line and the rest its continuation lines.
+    fn from_record(record: &str) -> Self {
+        let mut lines = split_log_lines(record);
+        let mut entry = Self::new(lines.next().unwrap_or_default());
+        for line in lines {
+            entry.push_continuation_line(line);
+        }
+        entry
+    }
+
+    fn push_continuation_line(&mut self, line: &str) {
+        self.continuation_lines
+            .push(SharedString::from(line.to_string()));
+        let plain_line = strip_ansi(line);
+        self.lowercase_continuation_lines
+            .push(plain_line.to_lowercase());
+        self.continuation_file_references
+            .push(file_references(&plain_line));
+    }
+
+    /// The bytes held for this line, counting the lowercase copies kept for matching so the
+    /// `max_bytes` budget reflects what the view actually stores.
+    fn byte_len(&self) -> usize {
+        self.text.len()
+            + self.lowercase_text.len()
+            + self
+                .continuation_lines
+                .iter()
+                .map(|line| line.len())
+                .sum::<usize>()
+            + self
+                .lowercase_continuation_lines
+                .iter()
+                .map(|line| line.len())
+                .sum::<usize>()
+    }
+
+    /// Returns the line and its continuation lines with escape sequences removed.
+    fn plain_text(&self) -> String {
+        iter::once(&self.text)
+            .chain(&self.continuation_lines)
+            .map(|line| strip_ansi(line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Returns the text to copy for this line. With `messages_only`, the entry's timestamp and
+    /// level are left out, while its continuation lines are kept as they are.
+    fn copy_text(&self, messages_only: bool) -> String {
+        if !messages_only {
+            return self.plain_text();
+        }
+        let text = strip_ansi(&self.text);
+        iter::once(Cow::Borrowed(log_message(&text)))
+            .chain(self.continuation_lines.iter().map(|line| strip_ansi(line)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// A time range filter picked from `TIME_WINDOW_PRESETS`. `since` is fixed when the window is
+/// picked, so the range doesn't slide forward as time passes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct TimeWindow {
+    duration: TimeDelta,
+    since: DateTime<FixedOffset>,
+}
+
+/// Parses the RFC 3339 timestamp that starts each log entry.
+fn parse_timestamp(line: &str) -> Option<DateTime<FixedOffset>> {
+    let timestamp = line.split_whitespace().next()?;
+    DateTime::parse_from_rfc3339(timestamp).ok()
+}
+
+/// Returns the time between the timestamps of two entries, if both have one and it's more than
+/// `threshold`.
+fn time_gap(previous_line: &str, line: &str, threshold: TimeDelta) -> Option<TimeDelta> {
+    let gap = parse_timestamp(&strip_ansi(line))? - parse_timestamp(&strip_ansi(previous_line))?;
+    (gap > threshold).then_some(gap)
+}
+
+/// Returns `line` without the timestamp and level that start each entry.
+fn log_message(line: &str) -> &str {
+    let mut message = line.trim_start();
+    let first_token = |text: &str| text.split(char::is_whitespace).next().unwrap_or_default();
+    if parse_timestamp(message).is_some() {
+        message = message[first_token(message).len()..].trim_start();
+    }
+    if parse_log_level(first_token(message)).is_some() {
+        message = message[first_token(message).len()..].trim_start();
+    }
+    message
+}
+
+/// Formats an age in its largest whole unit, such as "12s" or "3m".
+fn format_relative_time(age: TimeDelta) -> String {
+    let seconds = age.num_seconds().max(0);
+    match seconds {
+        0..60 => format!("{seconds}s"),
+        60..3600 => format!("{}m", seconds / 60),
+        3600..86400 => format!("{}h", seconds / 3600),
+        _ => format!("{}d", seconds / 86400),
+    }
+}
+
+/// Whether `line` continues the preceding entry rather than starting a new one. Entries start
+/// with a timestamp and level, so indented lines and lines without a level are continuations.
+fn is_continuation_line(line: &str) -> bool {
+    line.starts_with(char::is_whitespace) || parse_log_level(&strip_ansi(line)).is_none()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    const ALL: [LogLevel; 5] = [
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+        LogLevel::Trace,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            LogLevel::Error => "Error",
+            LogLevel::Warn => "Warn",
+            LogLevel::Info => "Info",
+            LogLevel::Debug => "Debug",
+            LogLevel::Trace => "Trace",
+        }
+    }
+
+    fn mask_bit(self) -> u8 {
+        1 << self as u8
+    }
+
+    /// The color of lines at this level, from the `level_colors` setting if it sets one.
+    fn color(self, cx: &App) -> Color {
+        match LogViewSettings::get_global(cx).level_colors[self as usize] {
+            Some(color) => Color::Custom(color),
+            None => self.theme_color(),
+        }
+    }
+
+    fn theme_color(self) -> Color {
+        match self {
+            LogLevel::Error => Color::Error,
+            LogLevel::Warn => Color::Warning,
+            LogLevel::Info => Color::Default,
+            LogLevel::Debug | LogLevel::Trace => Color::Muted,
+        }
+    }
+}
+
+/// Which side of a remote connection logged a line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogHost {
+    /// The Zed client the log view is open in.
+    Local,
+    /// The remote server, whose log messages the client writes to its own log.
+    Remote,
+}
+
+impl LogHost {
+    fn of_line(line: &str) -> Self {
+        if line.contains(REMOTE_SERVER_LOG_MARKER) {
+            LogHost::Remote
+        } else {
+            LogHost::Local
+        }
+    }
+}
+
+/// The set of levels shown in the log view.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct LevelMask(u8);
+
+impl LevelMask {
+    const ALL: Self = Self(0b11111);
+
+    fn contains(self, level: LogLevel) -> bool {
+        self.0 & level.mask_bit() != 0
+    }
+
+    fn toggle(&mut self, level: LogLevel) {
+        self.0 ^= level.mask_bit();
+    }
+
+    fn only(level: LogLevel) -> Self {
+        Self(level.mask_bit())
+    }
+
+    fn from_levels(levels: impl IntoIterator<Item = LogLevel>) -> Self {
+        Self(
+            levels
+                .into_iter()
+                .fold(0, |mask, level| mask | level.mask_bit()),
+        )
+    }
+
+    fn levels(self) -> impl Iterator<Item = LogLevel> {
+        LogLevel::ALL
+            .into_iter()
+            .filter(move |&level| self.contains(level))
+    }
+}
+
+impl From<LogViewLevel> for LogLevel {
+    fn from(level: LogViewLevel) -> Self {
+        match level {
+            LogViewLevel::Error => LogLevel::Error,
+            LogViewLevel::Warn => LogLevel::Warn,
+            LogViewLevel::Info => LogLevel::Info,
+            LogViewLevel::Debug => LogLevel::Debug,
+            LogViewLevel::Trace => LogLevel::Trace,
+        }
+    }
+}
+
+impl From<LogLevel> for LogViewLevel {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => LogViewLevel::Error,
+            LogLevel::Warn => LogViewLevel::Warn,
+            LogLevel::Info => LogViewLevel::Info,
+            LogLevel::Debug => LogViewLevel::Debug,
+            LogLevel::Trace => LogViewLevel::Trace,
+        }
+    }
+}
+
+/// A named query and level filter that can be applied from the toolbar in one step.
+#[derive(Clone, Debug, PartialEq)]
+struct FilterPreset {
+    name: SharedString,
+    query: String,
+    level_filter: LevelMask,
+}
+
+impl FilterPreset {
+    fn is_applied(&self, open_log: &OpenLogView) -> bool {
+        self.query == open_log.filter.query && self.level_filter == open_log.filter.level_filter
+    }
+}
+
+/// Returns the built-in filter presets followed by those saved in the settings.
+fn filter_presets(cx: &App) -> Vec<FilterPreset> {
+    let built_in = [
+        ("Errors Only", LevelMask::only(LogLevel::Error)),
+        (
+            "Warnings and Errors",
+            LevelMask::from_levels([LogLevel::Error, LogLevel::Warn]),
+        ),
+    ]
+    .map(|(name, level_filter)| FilterPreset {
+        name: name.into(),
+        query: String::new(),
+        level_filter,
+    });
+    let saved = LogViewSettings::get_global(cx)
+        .filter_presets
+        .iter()
+        .map(|preset| FilterPreset {
+            name: preset.name.clone().into(),
+            query: preset.query.clone(),
+            level_filter: preset.levels.as_ref().map_or(LevelMask::ALL, |levels| {
+                LevelMask::from_levels(levels.iter().map(|&level| LogLevel::from(level)))
+            }),
+        });
+    built_in.into_iter().chain(saved).collect()
+}
+
+/// The errors and warnings within one band of the level density gutter.
+#[derive(Clone, Copy, Debug)]
+struct LevelDensity {
+    first_item_ix: usize,
+    item_count: usize,
+    error_count: usize,
+    warning_count: usize,
+}
+
+/// The number of lines received in each of the last `LOG_RATE_WINDOW_SECS` seconds, counted in
+/// whole seconds since the view was opened.
+struct LogRate {
+    started_at: Instant,
+    /// The seconds in which lines were received, oldest first.
+    buckets: VecDeque<LogRateBucket>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct LogRateBucket {
+    second: u64,
+    line_count: usize,
+    /// The position of the first entry received in this second, counted from the first entry
+    /// ever loaded like `trimmed_line_count`.
+    first_entry: usize,
+}
+
+impl LogRate {
+    fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            buckets: VecDeque::new(),
+        }
+    }
+
+    fn now_second(&self) -> u64 {
+        self.started_at.elapsed().as_secs()
+    }
+
+    fn record(&mut self, second: u64, line_count: usize, first_entry: usize) {
+        match self.buckets.back_mut() {
+            Some(bucket) if bucket.second == second => bucket.line_count += line_count,
+            _ => self.buckets.push_back(LogRateBucket {
+                second,
+                line_count,
+                first_entry,
+            }),
+        }
+        while self
+            .buckets
+            .front()
+            .is_some_and(|bucket| bucket.second + LOG_RATE_WINDOW_SECS <= second)
+        {
+            self.buckets.pop_front();
+        }
+    }
+
+    fn recent_buckets(&self, now_second: u64) -> impl Iterator<Item = &LogRateBucket> {
+        self.buckets
+            .iter()
+            .filter(move |bucket| bucket.second + LOG_RATE_WINDOW_SECS > now_second)
+    }
+
+    fn has_lines(&self, now_second: u64) -> bool {
+        self.recent_buckets(now_second).next().is_some()
+    }
+
+    /// Returns the number of lines received in each second of the window ending at `now_second`,
+    /// oldest first.
+    fn line_counts(&self, now_second: u64) -> Vec<usize> {
+        let mut line_counts = vec![0; LOG_RATE_WINDOW_SECS as usize];
+        for bucket in self.recent_buckets(now_second) {
+            let age = now_second.saturating_sub(bucket.second);
+            line_counts[(LOG_RATE_WINDOW_SECS - 1 - age) as usize] += bucket.line_count;
+        }
+        line_counts
+    }
+
+    fn busiest_first_entry(&self, now_second: u64) -> Option<usize> {
+        self.recent_buckets(now_second)
+            .max_by_key(|bucket| bucket.line_count)
+            .map(|bucket| bucket.first_entry)
+    }
+}
+
+/// The number of loaded lines at each level, indexed by `LogLevel`.
+#[derive(Clone, Copy, Debug, Default)]
+struct LevelCounts([usize; LogLevel::ALL.len()]);
+
+impl LevelCounts {
+    fn get(&self, level: LogLevel) -> usize {
+        self.0[level as usize]
+    }
+
+    fn add(&mut self, line: &LogLine) {
+        if let Some(level) = parse_log_level(&strip_ansi(&line.text)) {
+            self.0[level as usize] += 1;
+        }
+    }
+
+    fn remove(&mut self, line: &LogLine) {
+        if let Some(level) = parse_log_level(&strip_ansi(&line.text)) {
+            self.0[level as usize] = self.0[level as usize].saturating_sub(1);
+        }
+    }
+}
+
+/// The number of loaded lines from each target, without the targets of trimmed lines.
+#[derive(Clone, Debug, Default)]
+struct TargetCounts(HashMap<String, usize>);
+
+impl TargetCounts {
+    fn add(&mut self, line: &LogLine) {
+        if let Some(target) = parse_log_target(&strip_ansi(&line.text)) {
+            *self.0.entry(target.to_string()).or_default() += 1;
+        }
+    }
+
+    fn remove(&mut self, line: &LogLine) {
+        let text = strip_ansi(&line.text);
+        let Some(target) = parse_log_target(&text) else {
+            return;
+        };
+        if let Some(count) = self.0.get_mut(target) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                self.0.remove(target);
+            }
+        }
+    }
+
+    /// Returns up to `limit` targets by descending line count, breaking ties alphabetically.
+    fn most_frequent(&self, limit: usize) -> Vec<String> {
+        let mut counts = self.0.iter().collect::<Vec<_>>();
+        counts.sort_unstable_by(|(target_a, count_a), (target_b, count_b)| {
+            count_b.cmp(count_a).then_with(|| target_a.cmp(target_b))
+        });
+        counts
+            .into_iter()
+            .take(limit)
+            .map(|(target, _)| target.clone())
+            .collect()
+    }
+}
+
+/// Parses the level token that follows the timestamp in Zed's log format,
+/// also accepting lines that begin with the level itself (e.g. `[ERROR] ...`).
+fn parse_log_level(line: &str) -> Option<LogLevel> {
+    line.split_whitespace().take(2).find_map(|token| {
+        let token = token.trim_start_matches('[').trim_end_matches(']');
+        if token.eq_ignore_ascii_case("error") {
+            Some(LogLevel::Error)
+        } else if token.eq_ignore_ascii_case("warn") || token.eq_ignore_ascii_case("warning") {
+            Some(LogLevel::Warn)
+        } else if token.eq_ignore_ascii_case("info") {
+            Some(LogLevel::Info)
+        } else if token.eq_ignore_ascii_case("debug") {
+            Some(LogLevel::Debug)
+        } else if token.eq_ignore_ascii_case("trace") {
+            Some(LogLevel::Trace)
+        } else {
+            None
+        }
+    })
+}
+
+/// The parsed query and level filter that decide whether a line matches, kept apart from the view
+/// so matching can be reasoned about (and tested) without a window or list state.
+///
+/// Queries match case-insensitively. A query wrapped in slashes is a regex, matched as plain text
+/// when it doesn't compile; any other query is split into terms that must all appear, `key=value`
+/// fields that must each appear as a whole token, and `-term`s that hide lines containing them.
+struct LogFilter {
+    query: String,
+    /// The lowercased terms a line must all contain when the query isn't a valid regex.
+    terms: Vec<String>,
+    /// The lowercased `key=value` pairs in the query, which must each appear as a whole token.
+    fields: Vec<(String, String)>,
+    /// The lowercased `-term`s in the query. A line whose header contains any of them is hidden.
+    excluded_terms: Vec<String>,
+    /// The query with its `-term`s removed, which is what fuzzy matching scores lines against.
+    fuzzy_query: String,
+    regex: Option<Regex>,
+    regex_error: Option<SharedString>,
+    /// Whether query terms only match whole words, like a regex's `\b` on either side.
+    whole_word: bool,
+    level_filter: LevelMask,
+}
+
+impl LogFilter {
+    fn new(whole_word: bool, level_filter: LevelMask) -> Self {
+        Self {
+            query: String::new(),
+            terms: Vec::new(),
+            fields: Vec::new(),
+            excluded_terms: Vec::new(),
+            fuzzy_query: String::new(),
+            regex: None,
+            regex_error: None,
+            whole_word,
+            level_filter,
+        }
+    }
+
+    fn set_query(&mut self, query: String) {
+        self.regex = None;
+        self.regex_error = None;
+        self.fields.clear();
+        self.excluded_terms.clear();
+        self.fuzzy_query.clear();
+        self.terms = match regex_pattern(&query) {
+            Some(pattern) => {
+                let whole_word_pattern;
+                let compiled_pattern = if self.whole_word {
+                    whole_word_pattern = format!(r"\b(?:{pattern})\b");
+                    whole_word_pattern.as_str()
+                } else {
+                    pattern
+                };
+                match RegexBuilder::new(compiled_pattern)
+                    .case_insensitive(true)
+                    .build()
+                {
+                    Ok(regex) => self.regex = Some(regex),
+                    Err(err) => self.regex_error = Some(err.to_string().into()),
+                }
+                vec![pattern.to_lowercase()]
+            }
+            None => {
+                let (excluded_terms, terms) = parse_query_terms(&query)
+                    .into_iter()
+                    .partition::<Vec<_>, _>(|term| excluded_term(term).is_some());
+                self.excluded_terms = excluded_terms
+                    .iter()
+                    .filter_map(|term| excluded_term(term))
+                    .map(str::to_string)
+                    .collect();
+                self.fuzzy_query = query
+                    .split_whitespace()
+                    .filter(|word| excluded_term(word).is_none())
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let (terms, fields) = terms
+                    .into_iter()
+                    .partition::<Vec<_>, _>(|term| parse_field(term).is_none());
+                self.fields = fields
+                    .iter()
+                    .filter_map(|field| parse_field(field))
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect();
+                terms
+            }
+        };
+        self.query = query;
+    }
+
+    /// Regex queries are compiled with the whole-word boundaries, so they're parsed again.
+    fn set_whole_word(&mut self, whole_word: bool) {
+        self.whole_word = whole_word;
+        self.set_query(std::mem::take(&mut self.query));
+    }
+
+    /// Whether a line on its own matches the query, containing none of its excluded terms. Levels
+    /// are checked separately with `matches_level`, as continuation lines don't have one.
+    /// `lowercase_line` is `line` lowercased, which callers keep so it isn't redone per query.
+    /// Escape sequences in `line` are only stripped for regex queries, the one case matched
+    /// against `line` rather than `lowercase_line`.
+    fn matches(&self, line: &str, lowercase_line: &str) -> bool {
+        !self.is_excluded(lowercase_line) && self.matches_query(line, lowercase_line)
+    }
+
+    fn matches_query(&self, line: &str, lowercase_line: &str) -> bool {
+        if self.query.is_empty() {
+            return true;
+        }
+
+        if let Some(regex) = &self.regex {
+            return regex.is_match(&strip_ansi(line));
+        }
+
+        self.terms.iter().all(|term| {
+            term_match_ranges(lowercase_line, term, self.whole_word)
+                .next()
+                .is_some()
+        }) && self.fields.iter().all(|(key, value)| {
+            line_fields(lowercase_line).any(|(_, line_key, line_value)| {
+                line_key == key.as_str() && line_value == value.as_str()
+            })
+        })
+    }
+
+    /// Returns the non-overlapping byte ranges of `line` matched by the query.
+    fn match_ranges(&self, line: &str) -> Vec<Range<usize>> {
+        if self.query.is_empty() {
+            return Vec::new();
+        }
+
+        if let Some(regex) = &self.regex {
+            return regex
+                .find_iter(line)
+                .map(|found| found.range())
+                .filter(|range| !range.is_empty())
+                .collect();
+        }
+
+        let line_lower = line.to_lowercase();
+        // Lowercasing can change the byte length of some non-ASCII text, in which case offsets
+        // into `line_lower` would not land on `line`'s char boundaries.
+        if line_lower.len() != line.len() {
+            return Vec::new();
+        }
+        let mut ranges = self
+            .terms
+            .iter()
+            .flat_map(|term| term_match_ranges(&line_lower, term, self.whole_word))
+            .chain(
+                line_fields(&line_lower).filter_map(|(range, line_key, line_value)| {
+                    self.fields
+                        .iter()
+                        .any(|(key, value)| {
+                            line_key == key.as_str() && line_value == value.as_str()
+                        })
+                        .then_some(range)
+                }),
+            )
+            .collect::<Vec<_>>();
+        ranges.sort_unstable_by_key(|range| range.start);
+
+        let mut merged_ranges: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged_ranges.last_mut() {
+                Some(last_range) if range.start <= last_range.end => {
+                    last_range.end = last_range.end.max(range.end);
+                }
+                _ => merged_ranges.push(range),
+            }
+        }
+        merged_ranges
+    }
+
+    fn matches_level(&self, line: &str) -> bool {
+        // Only a line at the very start of the log can lack a level, since any later one is
+        // attached to the preceding entry. There's nothing to filter it by, so keep it visible.
+        parse_log_level(line).is_none_or(|level| self.level_filter.contains(level))
+    }
+
+    fn is_excluded(&self, lowercase_line: &str) -> bool {
+        if self.excluded_terms.is_empty() {
+            return false;
+        }
+        self.excluded_terms.iter().any(|term| {
+            term_match_ranges(lowercase_line, term, self.whole_word)
+                .next()
+                .is_some()
+        })
+    }
+}
+
+/// Returns the byte ranges where `term` occurs in `text`. With `whole_word`, occurrences that
+/// are part of a longer word (`err` in `error`) are skipped.
+fn term_match_ranges<'a>(
+    text: &'a str,
+    term: &'a str,
+    whole_word: bool,
+) -> impl Iterator<Item = Range<usize>> + 'a {
+    text.match_indices(term)
+        .map(|(start, matched)| start..start + matched.len())
+        .filter(move |range| !whole_word || is_whole_word(text, range))
+}
+
+/// Whether `range` of `text` isn't joined to a word character on either side. A term that
+/// itself begins or ends with punctuation (such as `::lsp`) needs no boundary on that side.
+fn is_whole_word(text: &str, range: &Range<usize>) -> bool {
+    let is_word_character = |character: char| character.is_alphanumeric() || character == '_';
+    let starts_word = text[range.clone()]
+        .chars()
+        .next()
+        .is_none_or(|character| !is_word_character(character))
+        || text[..range.start]
+            .chars()
+            .next_back()
+            .is_none_or(|character| !is_word_character(character));
+    let ends_word = text[range.clone()]
+        .chars()
+        .next_back()
+        .is_none_or(|character| !is_word_character(character))
+        || text[range.end..]
+            .chars()
+            .next()
+            .is_none_or(|character| !is_word_character(character));
+    starts_word && ends_word
+}
+
+/// Parses the `[module::path]` target that Zed's log format writes after the level.
+fn parse_log_target(line: &str) -> Option<&str> {
+    line.split_whitespace().take(4).find_map(|token| {
+        let target = token.strip_prefix('[')?.strip_suffix(']')?;
+        (target.starts_with(|character: char| character.is_alphabetic() || character == '_')
+            && parse_log_level(token).is_none())
+        .then_some(target)
+    })
+}
+
+/// Returns the term to exclude when a query term is written as `-term`.
+fn excluded_term(term: &str) -> Option<&str> {
+    term.strip_prefix('-').filter(|term| !term.is_empty())
+}
+
+/// Returns the regex pattern when the query is written as `/pattern/`.
+fn regex_pattern(query: &str) -> Option<&str> {
+    query
+        .strip_prefix('/')
+        .and_then(|query| query.strip_suffix('/'))
+        .filter(|pattern| !pattern.is_empty())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AnsiColor {
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+impl AnsiColor {
+    fn hsla(self, cx: &App) -> Hsla {
+        match self {
+            AnsiColor::Indexed(index) => terminal::get_color_at_index(index as usize, cx.theme()),
+            AnsiColor::Rgb(red, green, blue) => Rgba {
+                r: red as f32 / 255.,
+                g: green as f32 / 255.,
+                b: blue as f32 / 255.,
+                a: 1.,
+            }
+            .into(),
+        }
+    }
+}
+
+/// The text attributes set by SGR (`ESC [ ... m`) escape sequences that the log view renders.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct AnsiStyle {
+    foreground: Option<AnsiColor>,
+    background: Option<AnsiColor>,
+    bold: bool,
+}
+
+impl AnsiStyle {
+    fn apply_sgr(&mut self, parameters: &str) {
+        let mut codes = parameters
+            .split([';', ':'])
+            .map(|code| code.parse::<u8>().unwrap_or(0));
+        while let Some(code) = codes.next() {
+            match code {
+                0 => *self = AnsiStyle::default(),
+                1 => self.bold = true,
+                22 => self.bold = false,
+                30..=37 => self.foreground = Some(AnsiColor::Indexed(code - 30)),
+                38 => self.foreground = parse_extended_color(&mut codes),
+                39 => self.foreground = None,
+                40..=47 => self.background = Some(AnsiColor::Indexed(code - 40)),
+                48 => self.background = parse_extended_color(&mut codes),
+                49 => self.background = None,
+                90..=97 => self.foreground = Some(AnsiColor::Indexed(code - 90 + 8)),
+                100..=107 => self.background = Some(AnsiColor::Indexed(code - 100 + 8)),
+                _ => {}
+            }
+        }
+    }
+
+    fn highlight(self, cx: &App) -> HighlightStyle {
+        HighlightStyle {
+            color: self.foreground.map(|color| color.hsla(cx)),
+            background_color: self.background.map(|color| color.hsla(cx)),
+            font_weight: self.bold.then_some(FontWeight::BOLD),
+            ..Default::default()
+        }
+    }
+}
+
+/// Parses the arguments of an extended `38`/`48` color code: `5;<index>` or `2;<r>;<g>;<b>`.
+fn parse_extended_color(codes: &mut impl Iterator<Item = u8>) -> Option<AnsiColor> {
+    match codes.next()? {
+        5 => Some(AnsiColor::Indexed(codes.next()?)),
+        2 => Some(AnsiColor::Rgb(codes.next()?, codes.next()?, codes.next()?)),
+        _ => None,
+    }
+}
+
+/// Removes every escape sequence from `line`, returning the remaining text together with the
+/// ranges of it styled by SGR sequences. Escape sequences other than SGR are dropped so they
+/// don't render as control characters.
+fn parse_ansi(line: &str) -> (Cow<'_, str>, Vec<(Range<usize>, AnsiStyle)>) {
+    if !line.contains('\x1b') {
+        return (Cow::Borrowed(line), Vec::new());
+    }
+
+    let mut text = String::with_capacity(line.len());
+    let mut styles = Vec::new();
+    let mut style = AnsiStyle::default();
+    let mut style_start = 0;
+    let mut rest = line;
+    while let Some(escape_start) = rest.find('\x1b') {
+        text.push_str(&rest[..escape_start]);
+        let sequence = &rest[escape_start + 1..];
+        let (sequence_len, sgr_parameters) = parse_escape_sequence(sequence);
+        if let Some(parameters) = sgr_parameters {
+            let mut new_style = style;
+            new_style.apply_sgr(parameters);
+            if new_style != style {
+                if style != AnsiStyle::default() && text.len() > style_start {
+                    styles.push((style_start..text.len(), style));
+                }
+                style = new_style;
+                style_start = text.len();
+            }
+        }
+        rest = &sequence[sequence_len..];
+    }
+    text.push_str(rest);
+    if style != AnsiStyle::default() && text.len() > style_start {
+        styles.push((style_start..text.len(), style));
+    }
+
+    (Cow::Owned(text), styles)
+}
+
+fn strip_ansi(line: &str) -> Cow<'_, str> {
+    parse_ansi(line).0
+}
+
+/// Returns the byte length of the escape sequence following an `ESC` character, and its
+/// parameters when it is an SGR sequence. Unterminated sequences extend to the end of the line.
+fn parse_escape_sequence(sequence: &str) -> (usize, Option<&str>) {
+    let bytes = sequence.as_bytes();
+    match bytes.first() {
+        // Control Sequence Introducer: parameter and intermediate bytes, then a final byte.
+        Some(b'[') => {
+            match bytes[1..]
+                .iter()
+                .position(|byte| (0x40..=0x7e).contains(byte))
+            {
+                Some(final_index) => {
+                    let final_index = final_index + 1;
+                    let parameters =
+                        (bytes[final_index] == b'm').then(|| &sequence[1..final_index]);
+                    (final_index + 1, parameters)
+                }
+                None => (sequence.len(), None),
+            }
+        }
+        // Operating System Command: terminated by BEL or `ESC \`.
+        Some(b']') => {
+            let terminator = bytes
+                .iter()
+                .enumerate()
+                .find_map(|(index, byte)| match byte {
+                    0x07 => Some(index + 1),
+                    0x1b if bytes.get(index + 1) == Some(&b'\\') => Some(index + 2),
+                    _ => None,
+                });
+            (terminator.unwrap_or(sequence.len()), None)
+        }
+        _ => (sequence.chars().next().map_or(0, char::len_utf8), None),
+    }
+}
+
+/// Splits a line ending in a JSON object or array into the text before the payload and the
+/// parsed payload. Returns `None` when the line has no trailing payload that parses.
+fn try_extract_json(line: &str) -> Option<(&str, serde_json::Value)> {
+    let trimmed_line = line.trim_end();
+    if !trimmed_line.ends_with(['}', ']']) {
+        return None;
+    }
+    trimmed_line
+        .match_indices(['{', '['])
+        .find_map(|(start, _)| {
+            let value = serde_json::from_str(&trimmed_line[start..]).ok()?;
+            Some((&line[..start], value))
+        })
+}
+
+/// The kinds of JSON token colored when pretty-printing a payload.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum JsonToken {
+    Key,
+    String,
+    Number,
+    Literal,
+}
+
+impl JsonToken {
+    fn syntax_name(self) -> &'static str {
+        match self {
+            JsonToken::Key => "property",
+            JsonToken::String => "string",
+            JsonToken::Number => "number",
+            JsonToken::Literal => "boolean",
+        }
+    }
+}
+
+/// Finds the byte ranges of the keys, strings, numbers and literals in serialized JSON.
+fn json_tokens(json: &str) -> Vec<(Range<usize>, JsonToken)> {
+    let bytes = json.as_bytes();
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let start = offset;
+        match bytes[offset] {
+            b'"' => {
+                offset += 1;
+                while offset < bytes.len() && bytes[offset] != b'"' {
+                    offset += if bytes[offset] == b'\\' { 2 } else { 1 };
+                }
+                offset = (offset + 1).min(bytes.len());
+                let is_key = json[offset..].trim_start().starts_with(':');
+                let token = if is_key {
+                    JsonToken::Key
+                } else {
+                    JsonToken::String
+                };
+                tokens.push((start..offset, token));
+            }
+            b'-' | b'0'..=b'9' => {
+                while offset < bytes.len()
+                    && matches!(
+                        bytes[offset],
+                        b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9'
+                    )
+                {
+                    offset += 1;
+                }
+                tokens.push((start..offset, JsonToken::Number));
+            }
+            b't' | b'f' | b'n' => {
+                while offset < bytes.len() && bytes[offset].is_ascii_alphabetic() {
+                    offset += 1;
+                }
+                tokens.push((start..offset, JsonToken::Literal));
+            }
+            _ => offset += 1,
+        }
+    }
+    tokens
+}
+
+/// Renders `json` indented over multiple lines, colored with the theme's syntax colors.
+fn render_json(json: &serde_json::Value, text_size: Rems, cx: &App) -> impl IntoElement {
+    let pretty_json = serde_json::to_string_pretty(json).unwrap_or_else(|_| json.to_string());
+    let syntax = cx.theme().syntax();
+    let highlights = json_tokens(&pretty_json)
+        .into_iter()
+        .filter_map(|(range, token)| Some((range, syntax.style_for_name(token.syntax_name())?)))
+        .collect::<Vec<_>>();
+    div()
+        .w_full()
+        .font_buffer(cx)
+        .text_size(text_size)
+        .child(StyledText::new(pretty_json).with_highlights(highlights))
+}
+
+/// Returns how many characters of `text` are past `char_limit`.
+fn chars_past_limit(text: &str, char_limit: usize) -> usize {
+    // A string's byte length bounds its character count, which avoids counting short lines.
+    if text.len() <= char_limit {
+        return 0;
+    }
+    text.chars().count().saturating_sub(char_limit)
+}
+
+/// Drops the runs a colorizer returned that don't fall on `text`'s char boundaries, which styling
+/// the text would panic on, and orders the rest.
+fn valid_styled_runs<T>(mut runs: Vec<(Range<usize>, T)>, text: &str) -> Vec<(Range<usize>, T)> {
+    runs.retain(|(range, _)| {
+        range.start < range.end
+            && text.is_char_boundary(range.start)
+            && text.is_char_boundary(range.end)
+    });
+    runs.sort_unstable_by_key(|(range, _)| range.start);
+    runs
+}
+
+/// Drops the styles starting at or after `len` and shortens those running past it.
+fn clip_ranges<T>(styles: Vec<(Range<usize>, T)>, len: usize) -> Vec<(Range<usize>, T)> {
+    styles
+        .into_iter()
+        .filter(|(range, _)| range.start < len)
+        .map(|(range, style)| (range.start..range.end.min(len), style))
+        .collect()
+}
+
+/// The kinds of text colored within a plain log line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LogToken {
+    String,
+    Number,
+    Path,
+    Uuid,
+}
+
+impl LogToken {
+    fn syntax_name(self) -> &'static str {
+        match self {
+            LogToken::String => "string",
+            LogToken::Number => "number",
+            LogToken::Path => "link_uri",
+            LogToken::Uuid => "constant",
+        }
+    }
+}
+
+fn is_word_delimiter(byte: u8) -> bool {
+    byte.is_ascii_whitespace()
+        || matches!(
+            byte,
+            b'"' | b'\''
+                | b'('
+                | b')'
+                | b'['
+                | b']'
+                | b'{'
+                | b'}'
+                | b'<'
+                | b'>'
+                | b','
+                | b';'
+                | b'='
+        )
+}
+
+fn is_uuid(word: &str) -> bool {
+    word.len() == 36
+        && word.bytes().enumerate().all(|(index, byte)| match index {
+            8 | 13 | 18 | 23 => byte == b'-',
+            _ => byte.is_ascii_hexdigit(),
+        })
+}
+
+/// Whether `word` looks like a file path, either by how it starts or by having a directory
+/// separator and a file extension. URLs are left alone.
+fn is_path(word: &str) -> bool {
+    if word.len() < 2 || word.contains("://") {
+        return false;
+    }
+    let starts_like_path = word.starts_with('/')
+        || word.starts_with("~/")
+        || word.starts_with("./")
+        || word.starts_with("../")
+        || word.as_bytes()[0].is_ascii_alphabetic() && word[1..].starts_with(":\\");
+    let has_extension = word
+        .rsplit(['/', '\\'])
+        .next()
+        .is_some_and(|file_name| file_name.trim_start_matches('.').contains('.'));
+    starts_like_path || word.contains(['/', '\\']) && has_extension
+}
+
+/// Finds the byte ranges of the quoted strings, UUIDs, paths and numbers in a log line. This is a
+/// heuristic meant to be cheap enough to run on every visible line, not a full lexer.
+fn log_tokens(text: &str) -> Vec<(Range<usize>, LogToken)> {
+    let bytes = text.as_bytes();
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let start = offset;
+        if bytes[offset] == b'"' {
+            offset += 1;
+            while offset < bytes.len() && bytes[offset] != b'"' {
+                offset += if bytes[offset] == b'\\' { 2 } else { 1 };
+            }
+            offset = (offset + 1).min(bytes.len());
+            tokens.push((start..offset, LogToken::String));
+            continue;
+        }
+        if is_word_delimiter(bytes[offset]) {
+            offset += 1;
+            continue;
+        }
+        while offset < bytes.len() && !is_word_delimiter(bytes[offset]) {
+            offset += 1;
+        }
+
+        // Punctuation ending a sentence isn't part of the word.
+        let word = text[start..offset].trim_end_matches(['.', ':']);
+        let word_end = start + word.len();
+        if is_uuid(word) {
+            tokens.push((start..word_end, LogToken::Uuid));
+        } else if is_path(word) {
+            tokens.push((start..word_end, LogToken::Path));
+        } else if word.starts_with(|character: char| character.is_ascii_digit())
+            || word.starts_with('-')
+                && word[1..].starts_with(|character: char| character.is_ascii_digit())
+        {
+            // Only the leading number is colored, leaving units such as `ms` alone.
+            let number_len = if word.starts_with("0x") {
+                2 + word[2..].bytes().take_while(u8::is_ascii_hexdigit).count()
+            } else {
+                1 + word[1..]
+                    .bytes()
+                    .take_while(|byte| {
+                        matches!(byte, b'0'..=b'9' | b'.' | b'_' | b':' | b'-' | b'+')
+                    })
+                    .count()
+            };
+            tokens.push((start..start + number_len, LogToken::Number));
+        }
+    }
+    tokens
+}
+
+/// A `path:line` or `path:line:column` reference in a log line, with 1-based line and column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct FileReference {
+    path: String,
+    row: u32,
+    column: Option<u32>,
+}
+
+fn parse_file_reference(word: &str) -> Option<FileReference> {
+    let (rest, last_number) = word.rsplit_once(':')?;
+    let last_number = last_number.parse::<u32>().ok()?;
+    let (path, row, column) = match rest
+        .rsplit_once(':')
+        .and_then(|(path, row)| Some((path, row.parse::<u32>().ok()?)))
+    {
+        Some((path, row)) => (path, row, Some(last_number)),
+        None => (rest, last_number, None),
+    };
+    (row > 0 && is_path(path)).then(|| FileReference {
+        path: path.to_string(),
+        row,
+        column,
+    })
+}
+
+/// Finds the `path:line[:column]` references in a log line, such as `src/main.rs:12:5`.
+fn file_references(text: &str) -> Vec<(Range<usize>, FileReference)> {
+    let bytes = text.as_bytes();
+    let mut references = Vec::new();
+    let mut offset = 0;
+    while offset < bytes.len() {
+        if is_word_delimiter(bytes[offset]) {
+            offset += 1;
+            continue;
+        }
+        let start = offset;
+        while offset < bytes.len() && !is_word_delimiter(bytes[offset]) {
+            offset += 1;
+        }
+        // Punctuation ending a sentence isn't part of the reference.
+        let word = text[start..offset].trim_end_matches(['.', ':']);
+        if let Some(reference) = parse_file_reference(word) {
+            references.push((start..start + word.len(), reference));
+        }
+    }
+    references
+}
+
+/// Removes the indices of the first `dropped_line_count` lines from the sorted `line_indices` and
+/// shifts the rest down to match. Returns how many indices were removed.
+fn trim_line_indices(line_indices: &mut Vec<usize>, dropped_line_count: usize) -> usize {
+    if dropped_line_count == 0 {
+        return 0;
+    }
+    let removed_count = line_indices.partition_point(|&line_index| line_index < dropped_line_count);
+    line_indices.drain(..removed_count);
+    for line_index in line_indices.iter_mut() {
+        *line_index -= dropped_line_count;
+    }
+    removed_count
+}
+
+/// Removes the indices of lines from `first_line_index` on from the sorted `line_indices`.
+/// Returns how many indices remain.
+fn truncate_line_indices(line_indices: &mut Vec<usize>, first_line_index: usize) -> usize {
+    let remaining_count = line_indices.partition_point(|&line_index| line_index < first_line_index);
+    line_indices.truncate(remaining_count);
+    remaining_count
+}
+
+/// Returns the length of `bytes` up to and including its last line ending, so that a line still
+/// being written is read once it's complete rather than split in two. A trailing `\r` isn't
+/// counted, as it may be the first half of a `\r\n` that hasn't been written yet.
+fn complete_lines_len(bytes: &[u8]) -> usize {
+    let Some(terminator_index) = bytes
+        .iter()
+        .rposition(|&byte| byte == b'\n' || byte == b'\r')
+    else {
+        return 0;
+    };
+    if bytes[terminator_index] == b'\r' && terminator_index + 1 == bytes.len() {
+        return complete_lines_len(&bytes[..terminator_index]);
+    }
+    terminator_index + 1
+}
+
+/// Groups the first `limit` entries of `items` the way `append_lines` does: each record is an
+/// entry when `is_record`, otherwise continuation lines join the line before them.
+fn parse_log_entries<'a>(
+    items: impl Iterator<Item = &'a str>,
+    is_record: bool,
+    limit: usize,
+) -> Vec<LogLine> {
+    let mut entries: Vec<LogLine> = Vec::new();
+    for item in items {
+        if !is_record
+            && is_continuation_line(item)
+            && let Some(last_entry) = entries.last_mut()
+        {
+            last_entry.push_continuation_line(item);
+            continue;
+        }
+        if entries.len() == limit {
+            break;
+        }
+        entries.push(if is_record {
+            LogLine::from_record(item)
+        } else {
+            LogLine::new(item)
+        });
+    }
+    entries
+}
+
+/// Splits `text` into lines ended by `\n`, `\r\n` or a lone `\r`, without the line endings.
+/// Like `str::lines`, a final line ending doesn't produce an empty line.
+fn split_log_lines(text: &str) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let Some(terminator_index) = rest.find(['\r', '\n']) else {
+            return Some(std::mem::take(&mut rest));
+        };
+        let line = &rest[..terminator_index];
+        let terminator_len = if rest[terminator_index..].starts_with("\r\n") {
+            2
+        } else {
+            1
+        };
+        rest = &rest[terminator_index + terminator_len..];
+        Some(line)
+    })
+}
+
+/// Splits log content into what `append_lines` takes: lines, or with a `record_separator`,
+/// records that may span several lines. Records with nothing but whitespace are skipped.
+fn split_log_content<'a>(
+    text: &'a str,
+    record_separator: Option<&'a RecordSeparator>,
+) -> impl Iterator<Item = &'a str> {
+    match record_separator {
+        Some(record_separator) => Either::Left(
+            record_separator
+                .regex
+                .split(text)
+                .filter(|record| !record.trim().is_empty()),
+        ),
+        None => Either::Right(split_log_lines(text)),
+    }
+}
+
+/// Like `complete_lines_len`, but with a `record_separator` a record is only complete once the
+/// separator after it has been written.
+fn complete_content_len(bytes: &[u8], record_separator: Option<&RecordSeparator>) -> usize {
+    match record_separator {
+        Some(record_separator) => record_separator
+            .bytes_regex
+            .find_iter(bytes)
+            .last()
+            .map_or(0, |separator| separator.end()),
+        None => complete_lines_len(bytes),
+    }
+}
+
+/// Returns the index in `filtered_indices` that is `delta` entries from the line `head`, clamped
+/// to the ends. When `head` is hidden by the filter, steps count from the gap it falls in, and
+/// without a `head` the result is `fallback`.
+fn step_filtered_index(
+    filtered_indices: &[usize],
+    head: Option<usize>,
+    fallback: usize,
+    delta: isize,
+) -> Option<usize> {
+    let last_index = filtered_indices.len().checked_sub(1)?;
+    let target = match head.map(|head| filtered_indices.binary_search(&head)) {
+        Some(Ok(index)) => index.saturating_add_signed(delta),
+        Some(Err(index)) if delta > 0 => index.saturating_add(delta.unsigned_abs() - 1),
+        Some(Err(index)) => index.saturating_sub(delta.unsigned_abs()),
+        None => fallback,
+    };
+    Some(target.min(last_index))
+}
+
+/// Returns how many entries at the end of `entries` fit within `max_bytes` when joined by
+/// newlines. The last entry is always counted, however long, so there's something to send.
+fn trailing_entry_count_within(entries: &[String], max_bytes: usize) -> usize {
+    let mut byte_count = 0;
+    let fitting_count = entries
+        .iter()
+        .rev()
+        .take_while(|entry| {
+            byte_count += entry.len() + 1;
+            byte_count <= max_bytes + 1
+        })
+        .count();
+    fitting_count.max(1).min(entries.len())
+}
+
+/// Wraps `text` in a fenced Markdown code block tagged with `language`, if it isn't empty. The
+/// fence is made longer than any run of backticks in `text`, so the block can't end early.
+fn markdown_code_block(text: &str, language: &str) -> String {
+    let longest_backtick_run = text
+        .split(|character| character != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest_backtick_run.max(2) + 1);
+    format!("{fence}{language}\n{text}\n{fence}")
+}
+
+/// Returns the length of the longest run of at most `max_len` lines that both ends `old_lines`
+/// and starts `new_lines`.
+fn rotation_overlap_len(old_lines: &[&str], new_lines: &[&str], max_len: usize) -> usize {
+    let max_len = max_len.min(old_lines.len()).min(new_lines.len());
+    (1..=max_len)
+        .rev()
+        .find(|&len| old_lines[old_lines.len() - len..] == new_lines[..len])
+        .unwrap_or(0)
+}
+
+/// Waits for `path` to be created. A path that doesn't exist can't be watched, so its parent
+/// directory is watched instead. Returns early if that can't be watched either.
+async fn wait_for_file(fs: &dyn Fs, path: &Path, poll_interval: Duration) {
+    let Some(parent) = path.parent() else {
+        return;
+    };
+    let (mut events, _watcher) = fs.watch(parent, poll_interval).await;
+    // Checked after the watch starts, so a file created in between isn't missed.
+    while !fs.is_file(path).await {
+        if events.next().await.is_none() {
+            return;
+        }
+    }
+}
+
+/// Reads the complete lines within the last `max_bytes` of `path`, or `None` when the whole file
+/// is no bigger, as it's then as quick to read all of it.
+async fn read_tail_preview(path: &Path, max_bytes: usize) -> anyhow::Result<Option<String>> {
+    let mut file = smol::fs::File::open(path).await?;
+    let len = file.metadata().await?.len() as usize;
+    if len <= max_bytes {
+        return Ok(None);
+    }
+    file.seek(SeekFrom::Start((len - max_bytes) as u64)).await?;
+    let mut bytes = vec![0; max_bytes];
+    file.read_exact(&mut bytes).await?;
+    Ok(Some(
+        String::from_utf8_lossy(tail_preview_lines(&bytes)).into_owned(),
+    ))
+}
+
+/// Trims `bytes`, read from partway through a file, to the lines it holds in full.
+fn tail_preview_lines(bytes: &[u8]) -> &[u8] {
+    let first_line_start = bytes
+        .iter()
+        .position(|&byte| byte == b'\n')
+        .map_or(bytes.len(), |newline_index| newline_index + 1);
+    let bytes = &bytes[first_line_start..];
+    &bytes[..complete_lines_len(bytes)]
+}
+
+/// Reads `path` from `offset` to its end. This goes through `smol` rather than `Fs`, which can
+/// only load whole files.
+async fn read_from_offset(path: &Path, offset: usize) -> anyhow::Result<Vec<u8>> {
+    let mut file = smol::fs::File::open(path).await?;
+    file.seek(SeekFrom::Start(offset as u64)).await?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).await?;
+    Ok(bytes)
+}
+
+/// Reads what was appended to `path` past `offset`, up to the end of its last complete line or
+/// record. Returns that text and its length in bytes, by which the offset should advance.
+async fn read_appended_content(
+    path: &Path,
+    offset: usize,
+    record_separator: Option<&RecordSeparator>,
+) -> anyhow::Result<(String, usize)> {
+    let appended = read_from_offset(path, offset).await?;
+    let complete_len = complete_content_len(&appended, record_separator);
+    Ok((
+        String::from_utf8_lossy(&appended[..complete_len]).into_owned(),
+        complete_len,
+    ))
+}
+
+/// Parses a `key=value` token, where the key is made of word characters, dots and dashes.
+fn parse_field(token: &str) -> Option<(&str, &str)> {
+    let (key, value) = token.split_once('=')?;
+    let is_key = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '-'));
+    (is_key && !value.is_empty()).then_some((key, value))
+}
+
+/// Returns the `key=value` tokens in `line` along with their byte ranges. Punctuation
+/// separating the pairs, like `(key=value, other=value)`, is not part of the token.
+fn line_fields(line: &str) -> impl Iterator<Item = (Range<usize>, &str, &str)> {
+    line.split_whitespace().filter_map(move |token| {
+        let token = token
+            .trim_start_matches(['(', '[', '{'])
+            .trim_end_matches([',', ';', ')', ']', '}']);
+        let (key, value) = parse_field(token)?;
+        let start = token.as_ptr() as usize - line.as_ptr() as usize;
+        Some((start..start + token.len(), key, value))
+    })
+}
+
+/// Splits a plain-text query into lowercased terms on whitespace, keeping `"quoted phrases"`
+/// together as a single term.
+fn parse_query_terms(query: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    let mut rest = query.trim_start();
+    while !rest.is_empty() {
+        let term = if let Some(phrase) = rest.strip_prefix('"') {
+            let (phrase, remainder) = phrase.split_once('"').unwrap_or((phrase, ""));
+            rest = remainder;
+            phrase
+        } else {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let (term, remainder) = rest.split_at(end);
+            rest = remainder;
+            term
+        };
+        if !term.is_empty() {
+            terms.push(term.to_lowercase());
+        }
+        rest = rest.trim_start();
+    }
+    terms
+}
+
+pub enum OpenLogEvent {
+    ShowToast(Toast),
+    /// The filter or follow state changed and should be re-serialized.
+    StateChanged,
+    /// The toolbar's filter box should take focus.
+    FocusFilter,
+    /// Every filter was removed from the view, so the toolbar's filter box should be emptied.
+    FilterCleared,
+    /// Errors were logged while following the log, and the user should be told about them.
+    ErrorsLogged(usize),
+    /// The watcher's status or the match count changed, so the tab should be redrawn.
+    TabChanged,
+}
+
+/// The state an `OpenLogView`'s tab reflects, in its icon and after its label.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct TabStatus {
+    watcher_disconnected: bool,
+    /// Whether new lines are being followed as they're written.
+    streaming: bool,
+    /// The number of lines passing the filter, while there is one.
+    match_count: Option<usize>,
+}
+
+/// The UI state of an `OpenLogView` restored when its tab is reopened.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SerializedOpenLogView {
+    search_query: String,
+    level_filter: LevelMask,
+    following: bool,
+    #[serde(default)]
+    source: LogSource,
+    #[serde(default)]
+    fuzzy_match: bool,
+    #[serde(default)]
+    whole_word: bool,
+    #[serde(default)]
+    invert_filter: bool,
+    #[serde(default)]
+    show_non_matching: bool,
+    #[serde(default)]
+    muted_targets: Vec<String>,
+    #[serde(default)]
+    shown_targets: Vec<String>,
+}
+
+impl EventEmitter<OpenLogEvent> for OpenLogView {}
+
+impl Item for OpenLogView {
+    type Event = OpenLogEvent;
+
+    fn tab_content_text(&self, _detail: usize, _cx: &App) -> SharedString {
+        let label = self.source.tab_label();
+        match self.tab_status().match_count {
+            Some(match_count) => format!("{label} ({match_count})").into(),
+            None => label,
+        }
+    }
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        let tab_status = self.tab_status();
+        Some(if tab_status.watcher_disconnected {
+            Icon::new(IconName::Warning).color(Color::Warning)
+        } else if tab_status.streaming {
+            Icon::new(IconName::PlayFilled).color(Color::Success)
+        } else {
+            Icon::new(IconName::File)
+        })
+    }
+
+    fn to_item_events(event: &Self::Event, f: &mut dyn FnMut(ItemEvent)) {
+        // The source, and so the tab's label, can change along with the rest of the state.
+        if let OpenLogEvent::StateChanged | OpenLogEvent::TabChanged = event {
+            f(ItemEvent::UpdateTab)
+        }
+    }
+
+    fn as_searchable(
+        &self,
+        handle: &Entity<Self>,
+        _: &App,
+    ) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(handle.clone()))
+    }
+}
+
+impl EventEmitter<SearchEvent> for OpenLogView {}
+
+impl SearchableItem for OpenLogView {
+    /// The index in `lines` of the entry a match is in, once for each match in it.
+    type Match = usize;
+
+    fn supported_options(&self) -> SearchOptions {
+        SearchOptions {
+            case: true,
+            word: true,
+            regex: true,
+            replacement: false,
+            selection: false,
+            select_all: false,
+            find_in_results: false,
+        }
+    }
+
+    fn clear_matches(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.search_bar_active_line.take().is_some() {
+            cx.notify();
+        }
+    }
+
+    fn update_matches(
+        &mut self,
+        _matches: &[Self::Match],
+        _active_match_index: Option<usize>,
+        _token: SearchToken,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) {
+    }
+
+    fn query_suggestion(
+        &mut self,
+        _seed_query_override: Option<SeedQuerySetting>,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> String {
+        String::new()
+    }
+
+    fn activate_match(
+        &mut self,
+        index: usize,
+        matches: &[Self::Match],
+        _token: SearchToken,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(&line_index) = matches.get(index) else {
+            return;
+        };
+        self.search_bar_active_line = Some(line_index);
+        if let Some(filtered_index) = self.filtered_index_of(line_index) {
+            self.reveal_entry(filtered_index, cx);
+        }
+        cx.notify();
+    }
+
+    fn select_matches(
+        &mut self,
+        _matches: &[Self::Match],
+        _token: SearchToken,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) {
+    }
+
+    fn replace(
+        &mut self,
+        _: &Self::Match,
+        _: &SearchQuery,
+        _token: SearchToken,
+        _window: &mut Window,
+        _: &mut Context<Self>,
+    ) {
+    }
+
+    fn find_matches(
+        &mut self,
+        query: Arc<SearchQuery>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Vec<Self::Match>> {
+        let entries = self
+            .filtered_indices
+            .iter()
+            .filter_map(|&line_index| {
+                let line = self.lines.get(line_index)?;
+                Some((line_index, line.copy_text(self.copy_messages_only)))
+            })
+            .collect::<Vec<_>>();
+        cx.background_spawn(async move {
+            entries
+                .into_iter()
+                .flat_map(|(line_index, text)| {
+                    query.search_str(&text).into_iter().map(move |_| line_index)
+                })
+                .collect()
+        })
+    }
+
+    fn active_match_index(
+        &mut self,
+        direction: Direction,
+        matches: &[Self::Match],
+        _token: SearchToken,
+        _window: &mut Window,
+        _cx: &mut Context<Self>,
+    ) -> Option<usize> {
+        if matches.is_empty() {
+            return None;
+        }
+        let Some(active_line) = self.search_bar_active_line else {
+            return Some(0);
+        };
+        // Compared by position in the list, as fuzzy queries order entries by score.
+        let active_filtered_index = self.filtered_index_of(active_line);
+        match direction {
+            Direction::Next => matches
+                .iter()
+                .position(|&line_index| self.filtered_index_of(line_index) >= active_filtered_index)
+                .or(Some(0)),
+            Direction::Prev => matches
+                .iter()
+                .rposition(|&line_index| {
+                    self.filtered_index_of(line_index) <= active_filtered_index
+                })
+                .or(Some(matches.len() - 1)),
+        }
+    }
+}
+
+impl SerializableItem for OpenLogView {
+    fn serialized_item_kind() -> &'static str {
+        "OpenLogView"
+    }
+
+    fn cleanup(
+        workspace_id: WorkspaceId,
+        alive_items: Vec<ItemId>,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Task<anyhow::Result<()>> {
+        let db = OpenLogViewDb::global(cx);
+        delete_unloaded_items(alive_items, workspace_id, "open_log_views", &db, cx)
+    }
+
+    fn deserialize(
+        project: Entity<Project>,
+        workspace: WeakEntity<Workspace>,
+        workspace_id: WorkspaceId,
+        item_id: ItemId,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<anyhow::Result<Entity<Self>>> {
+        let db = OpenLogViewDb::global(cx);
+        window.spawn(cx, async move |cx| {
+            let state = db
+                .get_state(item_id, workspace_id)?
+                .context("No log view to deserialize")?;
+            let serialized = serde_json::from_str::<SerializedOpenLogView>(&state).log_err();
+            let source = serialized
+                .as_ref()
+                .map(|serialized| serialized.source.clone())
+                .unwrap_or_default();
+            cx.update(|window, cx| {
+                let open_log =
+                    cx.new(|cx| OpenLogView::new(project, source, serialized, window, cx));
+                workspace.update(cx, |_, cx| show_toasts_in_workspace(&open_log, cx))?;
+                Ok(open_log)
+            })?
+        })
+    }
+
+    fn serialize(
+        &mut self,
+        workspace: &mut Workspace,
+        item_id: ItemId,
+        _closing: bool,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Option<Task<anyhow::Result<()>>> {
+        let workspace_id = workspace.database_id()?;
+        let state = serde_json::to_string(&SerializedOpenLogView {
+            search_query: self.filter.query.clone(),
+            level_filter: self.filter.level_filter,
+            following: self.following,
+            source: self.source.clone(),
+            fuzzy_match: self.fuzzy_match,
+            whole_word: self.filter.whole_word,
+            invert_filter: self.invert_filter,
+            show_non_matching: self.show_non_matching,
+            muted_targets: self.muted_targets(),
+            shown_targets: self.shown_targets.iter().cloned().collect(),
+        })
+        .log_err()?;
+
+        let db = OpenLogViewDb::global(cx);
+        Some(cx.background_spawn(async move { db.save_state(item_id, workspace_id, state).await }))
+    }
+
+    fn should_serialize(&self, event: &Self::Event) -> bool {
+        matches!(event, OpenLogEvent::StateChanged)
+    }
+}
+
+impl Focusable for OpenLogView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for OpenLogView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("OpenLogView")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(|this, _: &ClearView, _window, cx| this.clear_lines(cx)))
+            .on_action(cx.listener(|this, _: &ReloadLog, _window, cx| this.reload(cx)))
+            .on_action(cx.listener(|this, _: &ZoomIn, _window, cx| {
+                this.set_font_scale(this.font_scale + FONT_SCALE_STEP, cx)
+            }))
+            .on_action(cx.listener(|this, _: &ZoomOut, _window, cx| {
+                this.set_font_scale(this.font_scale - FONT_SCALE_STEP, cx)
+            }))
+            .on_action(cx.listener(|this, _: &ResetZoom, _window, cx| this.set_font_scale(1.0, cx)))
+            .on_action(
+                cx.listener(|this, _: &CopySelection, _window, cx| this.copy_selection(false, cx)),
+            )
+            .on_action(
+                cx.listener(|this, _: &SelectAllLines, _window, cx| this.select_all_lines(cx)),
+            )
+            .on_action(
+                cx.listener(|this, _: &CopyFileReference, _window, cx| {
+                    this.copy_file_reference(cx)
+                }),
+            )
+            .on_action(cx.listener(|this, _: &ShowOnlySelectedTarget, _window, cx| {
+                this.toggle_only_selected_target(cx)
+            }))
+            .on_action(cx.listener(|this, _: &ShowAllTargets, _window, cx| {
+                if !this.show_all_targets(cx) {
+                    cx.propagate();
+                }
+            }))
+            .on_action(cx.listener(|this, _: &ScrollToFirstError, _window, cx| {
+                this.scroll_to_first_error(cx)
+            }))
+            .on_action(
+                cx.listener(|this, _: &ScrollToNextError, _window, cx| {
+                    this.scroll_to_next_error(cx)
+                }),
+            )
+            .on_action(
+                cx.listener(|_, _: &FocusFilter, _window, cx| cx.emit(OpenLogEvent::FocusFilter)),
+            )
+            .on_action(cx.listener(|this, _: &TruncateLogFile, window, cx| {
+                this.truncate_log_file(window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &RevealLogDirectory, _window, cx| {
+                this.reveal_log_directory(cx)
+            }))
+            .on_action(
+                cx.listener(|this, _: &SelectNextLine, _window, cx| this.move_selection(1, cx)),
+            )
+            .on_action(
+                cx.listener(|this, _: &SelectPreviousLine, _window, cx| {
+                    this.move_selection(-1, cx)
+                }),
+            )
+            .on_action(cx.listener(|this, _: &SelectNextPage, _window, cx| {
+                this.move_selection(this.page_entry_count() as isize, cx)
+            }))
+            .on_action(cx.listener(|this, _: &SelectPreviousPage, _window, cx| {
+                this.move_selection(-(this.page_entry_count() as isize), cx)
+            }))
+            .on_action(cx.listener(|this, _: &SelectFirstLine, _window, cx| {
+                this.move_selection(isize::MIN, cx)
+            }))
+            .on_action(cx.listener(|this, _: &SelectLastLine, _window, cx| {
+                this.move_selection(isize::MAX, cx)
+            }))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .when(self.watcher_disconnected, |this| {
+                this.child(
+                    div().p_1().child(
+                        Banner::new()
+                            .severity(Severity::Warning)
+                            .child(Label::new(
+                                "Stopped watching the log file. New lines won't appear.",
+                            ))
+                            .action_slot(
+                                Button::new("reconnect_log_watcher", "Reconnect")
+                                    .label_size(LabelSize::Small)
+                                    .on_click(cx.listener(|this, _, _window, cx| {
+                                        this.reload(cx);
+                                    })),
+                            ),
+                    ),
+                )
+            })
+            .when_some(
+                self.source
+                    .relocated()
+                    .filter(|_| !self.relocation_dismissed),
+                |this, relocated_source| {
+                    this.child(
+                        div().p_1().child(
+                            Banner::new()
+                                .severity(Severity::Info)
+                                .child(Label::new(
+                                    "This log is from another logs directory than the one Zed now writes to.",
+                                ))
+                                .action_slot(
+                                    h_flex()
+                                        .gap_1()
+                                        .child(
+                                            Button::new("switch_log_source", "Show Current Log")
+                                                .label_size(LabelSize::Small)
+                                                .on_click(cx.listener(
+                                                    move |this, _, _window, cx| {
+                                                        this.set_source(
+                                                            relocated_source.clone(),
+                                                            cx,
+                                                        );
+                                                    },
+                                                )),
+                                        )
+                                        .child(
+                                            IconButton::new(
+                                                "dismiss_log_relocation",
+                                                IconName::Close,
+                                            )
+                                            .icon_size(IconSize::Small)
+                                            .tooltip(Tooltip::text("Dismiss"))
+                                            .on_click(cx.listener(|this, _, _window, cx| {
+                                                this.relocation_dismissed = true;
+                                                cx.notify();
+                                            })),
+                                        ),
+                                ),
+                        ),
+                    )
+                },
+            )
+            .when(!self.lines.is_empty(), |this| {
+                this.child(self.render_level_summary(cx))
+            })
+            .children(self.render_latest_error(cx))
+            .children(self.render_older_matches(cx))
+            .child(if self.loading && self.lines.is_empty() {
+                h_flex()
+                    .size_full()
+                    .gap_2()
+                    .justify_center()
+                    .items_center()
+                    .child(
+                        Icon::new(IconName::ArrowCircle)
+                            .size(IconSize::Small)
+                            .color(Color::Muted)
+                            .with_rotate_animation(2),
+                    )
+                    .child(Label::new("Loading log…").color(Color::Muted))
+                    .into_any()
+            } else if self.waiting_for_file && self.lines.is_empty() {
+                h_flex()
+                    .size_full()
+                    .justify_center()
+                    .items_center()
+                    .child(
+                        Label::new(format!(
+                            "Waiting for {} to be created…",
+                            self.source.tab_label()
+                        ))
+                        .color(Color::Muted),
+                    )
+                    .into_any()
+            } else if self.filtered_indices.is_empty() {
+                self.render_empty_state(cx).into_any()
+            } else {
+                h_flex()
+                    .relative()
+                    .size_full()
+                    .min_h_0()
+                    .flex_grow_1()
+                    .child(
+                        div()
+                            .id("log-entries")
+                            .size_full()
+                            .on_click(cx.listener(|this, _, _window, cx| this.clear_selection(cx)))
+                            .child(
+                                list(self.list_state.clone(), cx.processor(Self::render_entry))
+                                    .with_sizing_behavior(gpui::ListSizingBehavior::Auto)
+                                    .size_full(),
+                            )
+                            .vertical_scrollbar_for(&self.list_state, window, cx),
+                    )
+                    .child(self.render_level_density(cx))
+                    .children(self.render_new_lines_button(cx))
+                    .into_any()
+            })
+            .children(self.render_context_pane(cx))
+    }
+}
+
+pub struct OpenLogToolbarItemView {
+    open_log: Option<Entity<OpenLogView>>,
+    search_editor: Entity<Editor>,
+    exclusion_editor: Entity<Editor>,
+    search_history: VecDeque<String>,
+    _search_history_task: Option<Task<()>>,
+    /// Shown while naming a new filter preset for the current filter.
+    preset_name_editor: Option<Entity<Editor>>,
+    _open_log_subscriptions: Vec<Subscription>,
+}
+
+impl OpenLogToolbarItemView {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let search_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Filter lines, or /regex/...", window, cx);
+            editor
+        });
+
+        cx.subscribe(
+            &search_editor,
+            |this, editor, event: &editor::EditorEvent, cx| {
+                if let editor::EditorEvent::BufferEdited { .. } = event {
+                    let query = editor.read(cx).text(cx);
+                    if let Some(open_log) = &this.open_log {
+                        open_log.update(cx, |log, cx| {
+                            log.set_search_query(query, cx);
+                        });
+                    }
+                    this._search_history_task = Some(cx.spawn(async move |this, cx| {
+                        cx.background_executor()
+                            .timer(SEARCH_HISTORY_DEBOUNCE)
+                            .await;
+                        this.update(cx, |this, cx| this.push_search_history(cx))
+                            .ok();
+                    }));
+                }
+            },
+        )
+        .detach();
+
+        let exclusion_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Exclude lines...", window, cx);
+            editor
+        });
+
+        cx.subscribe(
+            &exclusion_editor,
+            |this, editor, event: &editor::EditorEvent, cx| {
+                if let editor::EditorEvent::BufferEdited { .. } = event {
+                    let query = editor.read(cx).text(cx);
+                    if let Some(open_log) = &this.open_log {
+                        open_log.update(cx, |log, cx| {
+                            log.set_exclusion_query(query, cx);
+                        });
+                    }
+                }
+            },
+        )
+        .detach();
+
+        Self {
+            open_log: None,
+            search_editor,
+            exclusion_editor,
+            search_history: VecDeque::new(),
+            _search_history_task: None,
+            preset_name_editor: None,
+            _open_log_subscriptions: Vec::new(),
+        }
+    }
+
+    fn focus_search_editor(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.search_editor.update(cx, |editor, cx| {
+            window.focus(&editor.focus_handle(cx), cx);
+            editor.select_all(&editor::actions::SelectAll, window, cx);
+        });
+    }
+
+    fn clear_search(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.search_editor.update(cx, |editor, cx| {
+            editor.set_text("", window, cx);
+        });
+        if let Some(open_log) = &self.open_log {
+            open_log.update(cx, |log, cx| log.set_search_query(String::new(), cx));
+        }
+    }
+
+    fn clear_exclusions(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.exclusion_editor.update(cx, |editor, cx| {
+            editor.set_text("", window, cx);
+        });
+        if let Some(open_log) = &self.open_log {
+            open_log.update(cx, |log, cx| log.set_exclusion_query(String::new(), cx));
+        }
+    }
+
+    fn push_search_history(&mut self, cx: &mut Context<Self>) {
+        let query = self.search_editor.read(cx).text(cx);
+        if query.trim().is_empty() {
+            return;
+        }
+        self.search_history.retain(|entry| *entry != query);
+        self.search_history.push_front(query);
+        self.search_history.truncate(SEARCH_HISTORY_LIMIT);
+        cx.notify();
+    }
+
+    fn select_search_history_entry(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.search_editor.update(cx, |editor, cx| {
+            editor.set_text(query.clone(), window, cx);
+        });
+        if let Some(open_log) = &self.open_log {
+            open_log.update(cx, |log, cx| log.set_search_query(query, cx));
+        }
+        self.push_search_history(cx);
+    }
+
+    fn apply_filter_preset(
+        &mut self,
+        preset: FilterPreset,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(open_log) = &self.open_log {
+            open_log.update(cx, |log, cx| log.apply_filter_preset(&preset, cx));
+        }
+        self.search_editor.update(cx, |editor, cx| {
+            editor.set_text(preset.query, window, cx);
+        });
+    }
+
+    fn start_saving_filter_preset(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let preset_name_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Preset name", window, cx);
+            editor
+        });
+        window.focus(&preset_name_editor.focus_handle(cx), cx);
+        self.preset_name_editor = Some(preset_name_editor);
+        cx.notify();
+    }
+
+    fn save_filter_preset(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(preset_name_editor) = self.preset_name_editor.take() else {
+            return;
+        };
+        let name = preset_name_editor.read(cx).text(cx).trim().to_string();
+        if let Some(open_log) = &self.open_log
+            && !name.is_empty()
+        {
+            let open_log = open_log.read(cx);
+            let preset = LogFilterPresetContent {
+                name,
+                query: open_log.filter.query.clone(),
+                levels: (open_log.filter.level_filter != LevelMask::ALL).then(|| {
+                    open_log
+                        .filter
+                        .level_filter
+                        .levels()
+                        .map(LogViewLevel::from)
+                        .collect()
+                }),
+            };
+            update_settings_file(<dyn Fs>::global(cx), cx, move |settings, _| {
+                let presets = settings
+                    .log_view
+                    .get_or_insert_default()
+                    .filter_presets
+                    .get_or_insert_default();
+                presets.retain(|existing| existing.name != preset.name);
+                presets.push(preset);
+            });
+        }
+        self.focus_search_editor(window, cx);
+        cx.notify();
+    }
+
+    fn cancel_saving_filter_preset(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.preset_name_editor = None;
+        self.focus_search_editor(window, cx);
+        cx.notify();
+    }
+}
+
+impl Render for OpenLogToolbarItemView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let Some(open_log) = self.open_log.as_ref() else {
+            return Empty.into_any_element();
+        };
+
+        let open_log_clone = open_log.clone();
+        let open_log = open_log.read(cx);
+        let has_lines = !open_log.lines.is_empty();
+        let has_filtered_lines = !open_log.filtered_indices.is_empty();
+        let show_line_numbers = open_log.show_line_numbers;
+        let show_context_pane = open_log.show_context_pane;
+        let wrap_lines = open_log.wrap_lines;
+        let compact = open_log.compact;
+        let show_relative_time = open_log.show_relative_time;
+        let relative_time_frozen = open_log.frozen_now.is_some();
+        let render_ansi = open_log.render_ansi;
+        let highlight_tokens = open_log.highlight_tokens;
+        let tint_rows_by_level = open_log.tint_rows_by_level;
+        let match_counts = open_log.is_filtered().then(|| open_log.match_counts());
+        let search_regex_error = open_log.filter.regex_error.clone();
+        let following = open_log.is_following();
+        let paused_line_count = open_log.paused_line_count();
+        let level_filter = open_log.filter.level_filter;
+        let has_time_window = open_log.time_window.is_some();
+        let has_muted_targets = !open_log.muted_targets.is_empty();
+        let target_chips = open_log.target_chips();
+        let is_remote_project = open_log.is_remote_project(cx);
+        let host_filter = open_log.host_filter();
+        let show_non_matching = open_log.show_non_matching;
+        let filter_context_line_count = open_log.filter_context_line_count;
+        let invert_filter = open_log.is_filter_inverted();
+        let fuzzy_match = open_log.is_fuzzy_match();
+        let whole_word = open_log.is_whole_word();
+        let search_full_file = open_log.is_searching_full_file();
+        let has_pinned_lines = open_log.has_pinned_lines();
+        let has_errors = open_log.has_errors();
+        let has_search_query = !open_log.search_query().is_empty();
+        let has_exclusion_query = !open_log.exclusion_query().is_empty();
+        let load_stats = open_log.load_stats();
+        let log_rate = open_log
+            .log_rate
+            .line_counts(open_log.log_rate.now_second());
+        let peak_log_rate = log_rate.iter().copied().max().unwrap_or_default();
+        let can_save_filter_preset =
+            !open_log.filter.query.is_empty() || open_log.filter.level_filter != LevelMask::ALL;
+
+        h_flex()
+            .key_context("OpenLogSearchBar")
+            .on_action(cx.listener(|this, _: &SelectNextMatch, _window, cx| {
+                this.push_search_history(cx);
+                if let Some(open_log) = &this.open_log {
+                    open_log.update(cx, |log, cx| log.select_next_match(cx));
+                }
+            }))
+            .on_action(cx.listener(|this, _: &ClearFilter, window, cx| {
+                this.clear_search(window, cx);
+            }))
+            .on_action(cx.listener(|this, _: &SelectPreviousMatch, _window, cx| {
+                if let Some(open_log) = &this.open_log {
+                    open_log.update(cx, |log, cx| log.select_previous_match(cx));
+                }
+            }))
+            .gap_2()
+            .child(
+                h_flex()
+                    .w(px(200.))
+                    .gap_1()
+                    .child(div().flex_1().child(self.search_editor.clone()))
+                    .when(has_search_query, |this| {
+                        this.child(
+                            IconButton::new("clear_log_filter", IconName::XCircle)
+                                .icon_size(IconSize::Small)
+                                .icon_color(Color::Muted)
+                                .tooltip(Tooltip::text("Clear Filter"))
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.clear_search(window, cx);
+                                })),
+                        )
+                    }),
+            )
+            .child(
+                h_flex()
+                    .w(px(160.))
+                    .gap_1()
+                    .child(div().flex_1().child(self.exclusion_editor.clone()))
+                    .when(has_exclusion_query, |this| {
+                        this.child(
+                            IconButton::new("clear_log_exclusions", IconName::XCircle)
+                                .icon_size(IconSize::Small)
+                                .icon_color(Color::Muted)
+                                .tooltip(Tooltip::text("Clear Exclusions"))
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.clear_exclusions(window, cx);
+                                })),
+                        )
+                    }),
+            )
+            .child(
+                PopoverMenu::new("log-search-history")
+                    .anchor(Anchor::TopLeft)
+                    .trigger_with_tooltip(
+                        IconButton::new("log_search_history", IconName::HistoryRerun)
+                            .icon_size(IconSize::Small)
+                            .disabled(self.search_history.is_empty()),
+                        Tooltip::text("Recent Searches"),
+                    )
+                    .menu({
+                        let toolbar = cx.entity();
+                        move |window, cx| {
+                            let toolbar = toolbar.clone();
+                            let search_history = toolbar.read(cx).search_history.clone();
+                            Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                                for query in search_history {
+                                    let toolbar = toolbar.clone();
+                                    menu = menu.entry(query.clone(), None, move |window, cx| {
+                                        toolbar.update(cx, |toolbar, cx| {
+                                            toolbar.select_search_history_entry(
+                                                query.clone(),
+                                                window,
+                                                cx,
+                                            );
+                                        });
+                                    });
+                                }
+                                menu
+                            }))
+                        }
+                    }),
+            )
+            .when_some(match_counts, |this, (match_count, line_count)| {
+                this.child(
+                    Label::new(match (invert_filter, match_count) {
+                        (false, 0) => "No matches".to_string(),
+                        (false, _) => format!("{match_count} / {line_count}"),
+                        (true, 0) => "All lines match".to_string(),
+                        (true, _) => format!("{match_count} / {line_count} not matching"),
+                    })
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+                )
+            })
+            .when_some(search_regex_error, |this, error| {
+                this.child(
+                    div()
+                        .id("search_regex_error")
+                        .child(
+                            Icon::new(IconName::Warning)
+                                .size(IconSize::Small)
+                                .color(Color::Warning),
+                        )
+                        .tooltip(Tooltip::text(format!(
+                            "Invalid regex, matching as plain text: {error}"
+                        ))),
+                )
+            })
+            .when(!target_chips.is_empty(), |this| {
+                this.child(
+                    h_flex()
+                        .gap_0p5()
+                        .children(target_chips.into_iter().enumerate().map(
+                            |(ix, (target, is_shown))| {
+                                let open_log = open_log_clone.clone();
+                                Button::new(("log-target-chip", ix), target.clone())
+                                    .label_size(LabelSize::XSmall)
+                                    .style(ButtonStyle::Filled)
+                                    .toggle_state(is_shown)
+                                    .tooltip(Tooltip::text(if is_shown {
+                                        "Stop Showing Only This Target"
+                                    } else {
+                                        "Show Only This Target"
+                                    }))
+                                    .on_click(move |_, _window, cx| {
+                                        open_log.update(cx, |log, cx| {
+                                            log.toggle_target_shown(&target, cx);
+                                        });
+                                    })
+                            },
+                        )),
+                )
+            })
+            .child(
+                IconButton::new("toggle_fuzzy_match", IconName::Sparkle)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(fuzzy_match)
+                    .tooltip(Tooltip::text("Fuzzy Match"))
+                    .on_click({
+                        let open_log = open_log_clone.clone();
+                        move |_, _window, cx| {
+                            open_log.update(cx, |log, cx| {
+                                log.toggle_fuzzy_match(cx);
+                            });
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("toggle_whole_word", IconName::WholeWord)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(whole_word)
+                    .tooltip(Tooltip::text("Match Whole Words"))
+                    .on_click({
+                        let open_log = open_log_clone.clone();
+                        move |_, _window, cx| {
+                            open_log.update(cx, |log, cx| {
+                                log.toggle_whole_word(cx);
+                            });
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("toggle_search_full_file", IconName::FileTextOutlined)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(search_full_file)
+                    .tooltip(Tooltip::text("Also Search Trimmed Entries on Disk"))
+                    .on_click({
+                        let open_log = open_log_clone.clone();
+                        move |_, _window, cx| {
+                            open_log.update(cx, |log, cx| {
+                                log.toggle_search_full_file(cx);
+                            });
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("toggle_invert_filter", IconName::ListX)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(invert_filter)
+                    .tooltip(Tooltip::text("Exclude Matching Lines"))
+                    .on_click({
+                        let open_log = open_log_clone.clone();
+                        move |_, _window, cx| {
+                            open_log.update(cx, |log, cx| {
+                                log.toggle_invert_filter(cx);
+                            });
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("toggle_show_non_matching", IconName::Eye)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(show_non_matching)
+                    .tooltip(Tooltip::text("Show Non-Matching Lines"))
+                    .on_click({
+                        let open_log = open_log_clone.clone();
+                        move |_, _window, cx| {
+                            open_log.update(cx, |log, cx| {
+                                log.toggle_show_non_matching(cx);
+                            });
+                        }
+                    }),
+            )
+            .child(
+                PopoverMenu::new("log-level-filter")
+                    .anchor(Anchor::TopRight)
+                    .trigger_with_tooltip(
+                        IconButton::new("log_level_filter", IconName::Filter)
+                            .icon_size(IconSize::Small)
+                            .toggle_state(level_filter != LevelMask::ALL),
+                        Tooltip::text("Filter by Level"),
+                    )
+                    .menu({
+                        let open_log = open_log_clone.clone();
+                        move |window, cx| {
+                            let open_log = open_log.clone();
+                            let level_filter = open_log.read(cx).filter.level_filter;
+                            Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                                for level in LogLevel::ALL {
+                                    let open_log = open_log.clone();
+                                    menu = menu.toggleable_entry(
+                                        level.label(),
+                                        level_filter.contains(level),
+                                        IconPosition::Start,
+                                        None,
+                                        move |_, cx| {
+                                            open_log.update(cx, |log, cx| {
+                                                log.toggle_level(level, cx);
+                                            });
+                                        },
+                                    );
+                                }
+                                menu
+                            }))
+                        }
+                    }),
+            )
+            .when(is_remote_project, |this| {
+                this.child(
+                    PopoverMenu::new("log-host-filter")
+                        .anchor(Anchor::TopRight)
+                        .trigger_with_tooltip(
+                            IconButton::new("log_host_filter", IconName::Server)
+                                .icon_size(IconSize::Small)
+                                .toggle_state(host_filter.is_some()),
+                            Tooltip::text("Local and Remote Logs"),
+                        )
+                        .menu({
+                            let open_log = open_log_clone.clone();
+                            move |window, cx| {
+                                let open_log = open_log.clone();
+                                Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                                    for (label, host) in [
+                                        ("Local and Remote", None),
+                                        ("Local Only", Some(LogHost::Local)),
+                                        ("Remote Server Only", Some(LogHost::Remote)),
+                                    ] {
+                                        let open_log = open_log.clone();
+                                        menu = menu.toggleable_entry(
+                                            label,
+                                            host_filter == host,
+                                            IconPosition::Start,
+                                            None,
+                                            move |_, cx| {
+                                                open_log.update(cx, |log, cx| {
+                                                    log.set_host_filter(host, cx);
+                                                });
+                                            },
+                                        );
+                                    }
+                                    menu
+                                }))
+                            }
+                        }),
+                )
+            })
+            .when(has_muted_targets, |this| {
+                this.child(
+                    PopoverMenu::new("log-muted-targets")
+                        .anchor(Anchor::TopRight)
+                        .trigger_with_tooltip(
+                            IconButton::new("log_muted_targets", IconName::EyeOff)
+                                .icon_size(IconSize::Small)
+                                .toggle_state(true),
+                            Tooltip::text("Muted Targets"),
+                        )
+                        .menu({
+                            let open_log = open_log_clone.clone();
+                            move |window, cx| {
+                                let open_log = open_log.clone();
+                                let muted_targets = open_log.read(cx).muted_targets();
+                                Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                                    for target in muted_targets {
+                                        let open_log = open_log.clone();
+                                        menu = menu.toggleable_entry(
+                                            target.clone(),
+                                            true,
+                                            IconPosition::Start,
+                                            None,
+                                            move |_, cx| {
+                                                open_log.update(cx, |log, cx| {
+                                                    log.toggle_target_muted(&target, cx);
+                                                });
+                                            },
+                                        );
+                                    }
+                                    menu.separator().entry("Unmute All", None, move |_, cx| {
+                                        open_log.update(cx, |log, cx| {
+                                            log.unmute_all_targets(cx);
+                                        });
+                                    })
+                                }))
+                            }
+                        }),
+                )
+            })
+            .child(
+                PopoverMenu::new("log-filter-presets")
+                    .anchor(Anchor::TopRight)
+                    .trigger_with_tooltip(
+                        IconButton::new("log_filter_presets", IconName::Bookmark)
+                            .icon_size(IconSize::Small),
+                        Tooltip::text("Filter Presets"),
+                    )
+                    .menu({
+                        let toolbar = cx.entity();
+                        let open_log = open_log_clone.clone();
+                        move |window, cx| {
+                            let toolbar = toolbar.clone();
+                            let presets = filter_presets(cx)
+                                .into_iter()
+                                .map(|preset| {
+                                    let is_applied = preset.is_applied(open_log.read(cx));
+                                    (preset, is_applied)
+                                })
+                                .collect::<Vec<_>>();
+                            Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                                for (preset, is_applied) in presets {
+                                    let toolbar = toolbar.clone();
+                                    menu = menu.toggleable_entry(
+                                        preset.name.clone(),
+                                        is_applied,
+                                        IconPosition::Start,
+                                        None,
+                                        move |window, cx| {
+                                            toolbar.update(cx, |toolbar, cx| {
+                                                toolbar.apply_filter_preset(
+                                                    preset.clone(),
+                                                    window,
+                                                    cx,
+                                                );
+                                            });
+                                        },
+                                    );
+                                }
+                                menu.separator().item(
+                                    ContextMenuEntry::new("Save Current Filter…")
+                                        .disabled(!can_save_filter_preset)
+                                        .handler(move |window, cx| {
+                                            toolbar.update(cx, |toolbar, cx| {
+                                                toolbar.start_saving_filter_preset(window, cx);
+                                            });
+                                        }),
+                                )
+                            }))
+                        }
+                    }),
+            )
+            .when_some(
+                self.preset_name_editor.clone(),
+                |this, preset_name_editor| {
+                    this.child(
+                        div()
+                            .key_context("OpenLogPresetName")
+                            .w(px(150.))
+                            .on_action(cx.listener(|this, _: &menu::Confirm, window, cx| {
+                                this.save_filter_preset(window, cx);
+                            }))
+                            .on_action(cx.listener(|this, _: &menu::Cancel, window, cx| {
+                                this.cancel_saving_filter_preset(window, cx);
+                            }))
+                            .child(preset_name_editor),
+                    )
+                },
+            )
+            .child(
+                PopoverMenu::new("log-filter-context")
+                    .anchor(Anchor::TopRight)
+                    .trigger_with_tooltip(
+                        IconButton::new("log_filter_context", IconName::ChevronUpDown)
+                            .icon_size(IconSize::Small)
+                            .toggle_state(filter_context_line_count > 0)
+                            .disabled(show_non_matching),
+                        Tooltip::text("Lines Around Matches"),
+                    )
+                    .menu({
+                        let open_log = open_log_clone.clone();
+                        move |window, cx| {
+                            let open_log = open_log.clone();
+                            let filter_context_line_count =
+                                open_log.read(cx).filter_context_line_count;
+                            Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                                for line_count in FILTER_CONTEXT_PRESETS {
+                                    let label = match line_count {
+                                        0 => "Matches Only".to_string(),
+                                        1 => "1 Line Around Matches".to_string(),
+                                        _ => format!("{line_count} Lines Around Matches"),
+                                    };
+                                    let open_log = open_log.clone();
+                                    menu = menu.toggleable_entry(
+                                        label,
+                                        filter_context_line_count == line_count,
+                                        IconPosition::Start,
+                                        None,
+                                        move |_, cx| {
+                                            open_log.update(cx, |log, cx| {
+                                                log.set_filter_context_line_count(line_count, cx);
+                                            });
+                                        },
+                                    );
+                                }
+                                menu
+                            }))
+                        }
+                    }),
+            )
+            .child(
+                PopoverMenu::new("log-time-window")
+                    .anchor(Anchor::TopRight)
+                    .trigger_with_tooltip(
+                        IconButton::new("log_time_window", IconName::CountdownTimer)
+                            .icon_size(IconSize::Small)
+                            .toggle_state(has_time_window),
+                        Tooltip::text("Filter by Time"),
+                    )
+                    .menu({
+                        let open_log = open_log_clone.clone();
+                        move |window, cx| {
+                            let open_log = open_log.clone();
+                            let (time_window, keep_untimed_lines) = {
+                                let open_log = open_log.read(cx);
+                                (
+                                    open_log.time_window.map(|time_window| time_window.duration),
+                                    open_log.keep_untimed_lines,
+                                )
+                            };
+                            Some(ContextMenu::build(window, cx, move |mut menu, _, _| {
+                                menu = menu.toggleable_entry(
+                                    "All Time",
+                                    time_window.is_none(),
+                                    IconPosition::Start,
+                                    None,
+                                    {
+                                        let open_log = open_log.clone();
+                                        move |_, cx| {
+                                            open_log.update(cx, |log, cx| {
+                                                log.set_time_window(None, cx);
+                                            });
+                                        }
+                                    },
+                                );
+                                for (label, minutes) in TIME_WINDOW_PRESETS {
+                                    let duration = TimeDelta::minutes(minutes);
+                                    let open_log = open_log.clone();
+                                    menu = menu.toggleable_entry(
+                                        label,
+                                        time_window == Some(duration),
+                                        IconPosition::Start,
+                                        None,
+                                        move |_, cx| {
+                                            open_log.update(cx, |log, cx| {
+                                                log.set_time_window(Some(duration), cx);
+                                            });
+                                        },
+                                    );
+                                }
+                                menu.separator().toggleable_entry(
+                                    "Keep Lines Without Timestamps",
+                                    keep_untimed_lines,
+                                    IconPosition::Start,
+                                    None,
+                                    move |_, cx| {
+                                        open_log.update(cx, |log, cx| {
+                                            log.toggle_keep_untimed_lines(cx);
+                                        });
+                                    },
+                                )
+                            }))
+                        }
+                    }),
+            )
+            .when(!following && paused_line_count > 0, |this| {
+                this.child(
+                    Label::new(if paused_line_count == 1 {
+                        "1 new line".to_string()
+                    } else {
+                        format!("{paused_line_count} new lines")
+                    })
+                    .size(LabelSize::Small)
+                    .color(Color::Accent),
+                )
+            })
+            .child(
+                IconButton::new(
+                    "toggle_following",
+                    if following {
+                        IconName::DebugPause
+                    } else {
+                        IconName::PlayFilled
+                    },
+                )
+                .icon_size(IconSize::Small)
+                .tooltip(Tooltip::text(if following {
+                    "Pause Log"
+                } else {
+                    "Resume Log"
+                }))
+                .on_click({
+                    let open_log = open_log_clone.clone();
+                    move |_, _window, cx| {
+                        open_log.update(cx, |log, cx| {
+                            log.set_following(!following, cx);
+                        });
+                    }
+                }),
+            )
+            .child(
+                IconButton::new("scroll_to_top", IconName::ArrowUp)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::text("Jump to Top"))
+                    .disabled(!has_filtered_lines)
+                    .on_click({
+                        let open_log = open_log_clone.clone();
+                        move |_, _window, cx| {
+                            open_log.update(cx, |log, cx| {
+                                log.scroll_to_top(cx);
+                            });
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("scroll_to_bottom", IconName::ArrowDown)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::text("Jump to Bottom"))
+                    .disabled(!has_filtered_lines)
+                    .on_click({
+                        let open_log = open_log_clone.clone();
+                        move |_, _window, cx| {
+                            open_log.update(cx, |log, cx| {
+                                log.scroll_to_bottom(cx);
+                            });
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("previous_pinned_line", IconName::ChevronUp)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::text("Previous Pinned Line"))
+                    .disabled(!has_pinned_lines)
+                    .on_click({
+                        let open_log = open_log_clone.clone();
+                        move |_, _window, cx| {
+                            open_log.update(cx, |log, cx| {
+                                log.select_previous_pinned_line(cx);
+                            });
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("next_pinned_line", IconName::ChevronDown)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::text("Next Pinned Line"))
+                    .disabled(!has_pinned_lines)
+                    .on_click({
+                        let open_log = open_log_clone.clone();
+                        move |_, _window, cx| {
+                            open_log.update(cx, |log, cx| {
+                                log.select_next_pinned_line(cx);
+                            });
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("scroll_to_first_error", IconName::XCircle)
+                    .icon_size(IconSize::Small)
+                    .icon_color(if has_errors {
+                        Color::Error
+                    } else {
+                        Color::Default
+                    })
+                    .tooltip(Tooltip::text("Jump to First Error"))
+                    .disabled(!has_errors)
+                    .on_click({
+                        let open_log = open_log_clone.clone();
+                        move |_, _window, cx| {
+                            open_log.update(cx, |log, cx| {
+                                log.scroll_to_first_error(cx);
+                            });
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("scroll_to_next_error", IconName::ArrowDownRight)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::text("Jump to Next Error"))
+                    .disabled(!has_errors)
+                    .on_click({
+                        let open_log = open_log_clone.clone();
+                        move |_, _window, cx| {
+                            open_log.update(cx, |log, cx| {
+                                log.scroll_to_next_error(cx);
+                            });
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new(
+                    "toggle_wrap_lines",
+                    if wrap_lines {
+                        IconName::TextWrap
+                    } else {
+                        IconName::TextUnwrap
+                    },
+                )
+                .icon_size(IconSize::Small)
+                .toggle_state(wrap_lines)
+                .tooltip(Tooltip::text("Toggle Line Wrapping"))
+                .on_click({
+                    let open_log = open_log_clone.clone();
+                    move |_, _window, cx| {
+                        open_log.update(cx, |log, cx| {
+                            log.toggle_wrap_lines(cx);
+                        });
+                    }
+                }),
+            )
+            .child(
+                IconButton::new("toggle_compact", IconName::ListCollapse)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(compact)
+                    .tooltip(Tooltip::text("Compact Rows"))
+                    .on_click({
+                        let open_log = open_log_clone.clone();
+                        move |_, _window, cx| {
+                            open_log.update(cx, |log, cx| {
+                                log.toggle_compact(cx);
+                            });
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("toggle_render_ansi", IconName::Terminal)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(render_ansi)
+                    .tooltip(Tooltip::text("Toggle ANSI Colors"))
+                    .on_click({
+                        let open_log = open_log_clone.clone();
+                        move |_, _window, cx| {
+                            open_log.update(cx, |log, cx| {
+                                log.toggle_render_ansi(cx);
+                            });
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("toggle_highlight_tokens", IconName::Code)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(highlight_tokens)
+                    .tooltip(Tooltip::text("Highlight Paths, Numbers and Strings"))
+                    .on_click({
+                        let open_log = open_log_clone.clone();
+                        move |_, _window, cx| {
+                            open_log.update(cx, |log, cx| {
+                                log.toggle_highlight_tokens(cx);
+                            });
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("toggle_tint_rows_by_level", IconName::SquareDot)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(tint_rows_by_level)
+                    .tooltip(Tooltip::text("Tint Error and Warning Rows"))
+                    .on_click({
+                        let open_log = open_log_clone.clone();
+                        move |_, _window, cx| {
+                            open_log.update(cx, |log, cx| {
+                                log.toggle_tint_rows_by_level(cx);
+                            });
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("toggle_relative_time", IconName::Clock)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(show_relative_time)
+                    .tooltip(Tooltip::text("Show Relative Times"))
+                    .on_click({
+                        let open_log = open_log_clone.clone();
+                        move |_, _window, cx| {
+                            open_log.update(cx, |log, cx| {
+                                log.toggle_relative_time(cx);
+                            });
+                        }
+                    }),
+            )
+            .when(show_relative_time, |this| {
+                this.child(
+                    IconButton::new("freeze_relative_time", IconName::Lock)
+                        .icon_size(IconSize::Small)
+                        .toggle_state(relative_time_frozen)
+                        .tooltip(Tooltip::text(if relative_time_frozen {
+                            "Unfreeze Relative Times"
+                        } else {
+                            "Freeze Relative Times"
+                        }))
+                        .on_click({
+                            let open_log = open_log_clone.clone();
+                            move |_, _window, cx| {
+                                open_log.update(cx, |log, cx| {
+                                    log.toggle_freeze_relative_time(cx);
+                                });
+                            }
+                        }),
+                )
+            })
+            .child(
+                IconButton::new("toggle_line_numbers", IconName::Hash)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(show_line_numbers)
+                    .tooltip(Tooltip::text("Toggle Line Numbers"))
+                    .on_click({
+                        let open_log = open_log_clone.clone();
+                        move |_, _window, cx| {
+                            open_log.update(cx, |log, cx| {
+                                log.toggle_line_numbers(cx);
+                            });
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("toggle_context_pane", IconName::SplitAlt)
+                    .icon_size(IconSize::Small)
+                    .toggle_state(show_context_pane)
+                    .tooltip(Tooltip::text("Show Surrounding Lines"))
+                    .on_click({
+                        let open_log = open_log_clone.clone();
+                        move |_, _window, cx| {
+                            open_log.update(cx, |log, cx| {
+                                log.toggle_context_pane(cx);
+                            });
+                        }
+                    }),
+            )
+            .child(
+                PopoverMenu::new("log-copy-menu")
+                    .anchor(Anchor::TopRight)
+                    .trigger_with_tooltip(
+                        IconButton::new("copy_lines", IconName::Copy)
+                            .icon_size(IconSize::Small)
+                            .disabled(!has_filtered_lines),
+                        Tooltip::text("Copy Lines"),
+                    )
+                    .menu({
+                        let open_log = open_log_clone.clone();
+                        move |window, cx| {
+                            let open_log = open_log.clone();
+                            let log = open_log.read(cx);
+                            let has_selection = log.selected_line_range().is_some();
+                            let copy_messages_only = log.is_copying_messages_only();
+                            Some(ContextMenu::build(window, cx, move |menu, _, _| {
+                                menu.entry("Copy Filtered Lines", None, {
+                                    let open_log = open_log.clone();
+                                    move |_, cx| {
+                                        open_log.update(cx, |log, cx| {
+                                            log.copy_filtered_lines(false, cx)
+                                        });
+                                    }
+                                })
+                                .item(
+                                    ContextMenuEntry::new("Copy Selection")
+                                        .action(Box::new(CopySelection))
+                                        .disabled(!has_selection)
+                                        .handler({
+                                            let open_log = open_log.clone();
+                                            move |_, cx| {
+                                                open_log.update(cx, |log, cx| {
+                                                    log.copy_selection(false, cx)
+                                                });
+                                            }
+                                        }),
+                                )
+                                .separator()
+                                .entry("Copy Filtered Lines as Markdown", None, {
+                                    let open_log = open_log.clone();
+                                    move |_, cx| {
+                                        open_log.update(cx, |log, cx| {
+                                            log.copy_filtered_lines(true, cx)
+                                        });
+                                    }
+                                })
+                                .item(
+                                    ContextMenuEntry::new("Copy Selection as Markdown")
+                                        .disabled(!has_selection)
+                                        .handler({
+                                            let open_log = open_log.clone();
+                                            move |_, cx| {
+                                                open_log.update(cx, |log, cx| {
+                                                    log.copy_selection(true, cx)
+                                                });
+                                            }
+                                        }),
+                                )
+                                .item(
+                                    ContextMenuEntry::new("Copy File Reference")
+                                        .action(Box::new(CopyFileReference))
+                                        .disabled(!has_selection)
+                                        .handler({
+                                            let open_log = open_log.clone();
+                                            move |_, cx| {
+                                                open_log.update(cx, |log, cx| {
+                                                    log.copy_file_reference(cx)
+                                                });
+                                            }
+                                        }),
+                                )
+                                .separator()
+                                .item(
+                                    ContextMenuEntry::new(if has_selection {
+                                        "Add Selection to Agent Thread"
+                                    } else {
+                                        "Add Filtered Lines to Agent Thread"
+                                    })
+                                    .action(Box::new(AddToAgentThread))
+                                    .handler(|window, cx| {
+                                        window.dispatch_action(Box::new(AddToAgentThread), cx)
+                                    }),
+                                )
+                                .separator()
+                                .toggleable_entry(
+                                    "Leave Out Timestamps and Levels",
+                                    copy_messages_only,
+                                    IconPosition::Start,
+                                    None,
+                                    move |_, cx| {
+                                        open_log.update(cx, |log, cx| {
+                                            log.toggle_copy_messages_only(cx)
+                                        });
+                                    },
+                                )
+                            }))
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("reload_log", IconName::RotateCw)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::for_action_title("Reload Log", &ReloadLog))
+                    .on_click({
+                        let open_log = open_log_clone.clone();
+                        move |_, _window, cx| {
+                            open_log.update(cx, |log, cx| {
+                                log.reload(cx);
+                            });
+                        }
+                    }),
+            )
+            .child(
+                PopoverMenu::new("log-clear-menu")
+                    .anchor(Anchor::TopRight)
+                    .trigger_with_tooltip(
+                        IconButton::new("clear_lines", IconName::Trash).icon_size(IconSize::Small),
+                        Tooltip::text("Clear Log"),
+                    )
+                    .menu({
+                        let open_log = open_log_clone.clone();
+                        move |window, cx| {
+                            let open_log = open_log.clone();
+                            Some(ContextMenu::build(window, cx, move |menu, _, _| {
+                                menu.item(
+                                    ContextMenuEntry::new("Clear View")
+                                        .action(Box::new(ClearView))
+                                        .disabled(!has_lines)
+                                        .handler({
+                                            let open_log = open_log.clone();
+                                            move |_, cx| {
+                                                open_log.update(cx, |log, cx| log.clear_lines(cx));
+                                            }
+                                        }),
+                                )
+                                .entry(
+                                    "Truncate Log File…",
+                                    Some(Box::new(TruncateLogFile)),
+                                    move |window, cx| {
+                                        open_log.update(cx, |log, cx| {
+                                            log.truncate_log_file(window, cx)
+                                        });
+                                    },
+                                )
+                            }))
+                        }
+                    }),
+            )
+            .when(peak_log_rate > 0, |this| {
+                let bar_color = Color::Accent.color(cx);
+                this.child(
+                    h_flex()
+                        .id("log_rate_sparkline")
+                        .h(px(14.))
+                        .items_end()
+                        .cursor_pointer()
+                        .children(log_rate.into_iter().map(|line_count| {
+                            let share = line_count as f32 / peak_log_rate as f32;
+                            div().w(px(1.5)).h(px((14. * share).max(1.))).bg(bar_color)
+                        }))
+                        .tooltip(move |_window, cx| {
+                            Tooltip::with_meta(
+                                format!("Up to {peak_log_rate} lines per second this minute"),
+                                None,
+                                "Click to scroll to the busiest second",
+                                cx,
+                            )
+                        })
+                        .on_click({
+                            let open_log = open_log_clone.clone();
+                            move |_, _window, cx| {
+                                open_log.update(cx, |log, cx| log.scroll_to_busiest_second(cx));
+                            }
+                        }),
+                )
+            })
+            .child(
+                IconButton::new("reveal_log_directory", IconName::FolderOpen)
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::for_action_title(
+                        ui::utils::reveal_in_file_manager_label(false),
+                        &RevealLogDirectory,
+                    ))
+                    .on_click({
+                        let open_log = open_log_clone.clone();
+                        move |_, _window, cx| {
+                            open_log.update(cx, |log, cx| log.reveal_log_directory(cx));
+                        }
+                    }),
+            )
+            .child(
+                IconButton::new("open_log_file", IconName::File)
+                    .icon_size(IconSize::Small)
+                    .tooltip(move |_window, cx| match load_stats {
+                        Some(load_stats) => Tooltip::with_meta(
+                            "Open Raw Log File",
+                            None,
+                            format!(
+                                "Loaded {} in {} ms",
+                                util::size::format_file_size(load_stats.byte_len, true),
+                                load_stats.load_time.as_millis()
+                            ),
+                            cx,
+                        ),
+                        None => Tooltip::simple("Open Raw Log File", cx),
+                    })
+                    .on_click({
+                        let open_log = open_log_clone.clone();
+                        move |_, window, cx| {
+                            let open_log = open_log.read(cx);
+                            let path = open_log.source.path().to_path_buf();
+                            let line_text = open_log.selected_line_text();
+                            open_raw_log_file(path, line_text, window, cx);
+                        }
+                    }),
+            )
+            .into_any()
+    }
+}
+
+impl EventEmitter<ToolbarItemEvent> for OpenLogToolbarItemView {}
+
+impl ToolbarItemView for OpenLogToolbarItemView {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> ToolbarItemLocation {
+        if let Some(item) = active_pane_item
+            && let Some(open_log) = item.downcast::<OpenLogView>()
+        {
+            let search_query = open_log.read(cx).search_query().to_string();
+            self.search_editor.update(cx, |editor, cx| {
+                if editor.text(cx) != search_query {
+                    editor.set_text(search_query, window, cx);
+                }
+            });
+            let exclusion_query = open_log.read(cx).exclusion_query().to_string();
+            self.exclusion_editor.update(cx, |editor, cx| {
+                if editor.text(cx) != exclusion_query {
+                    editor.set_text(exclusion_query, window, cx);
+                }
+            });
+            self._open_log_subscriptions = vec![
+                cx.observe(&open_log, |_, _, cx| cx.notify()),
+                cx.subscribe_in(
+                    &open_log,
+                    window,
+                    |this, _, event, window, cx| match event {
+                        OpenLogEvent::FocusFilter => this.focus_search_editor(window, cx),
+                        OpenLogEvent::FilterCleared => {
+                            this.search_editor.update(cx, |editor, cx| {
+                                editor.set_text("", window, cx);
+                            });
+                            this.exclusion_editor.update(cx, |editor, cx| {
+                                editor.set_text("", window, cx);
+                            });
+                        }
+                        _ => {}
+                    },
+                ),
+            ];
+            self.open_log = Some(open_log);
+            cx.notify();
+            return ToolbarItemLocation::PrimaryRight;
+        }
+        self._open_log_subscriptions.clear();
+        if self.open_log.take().is_some() {
+            cx.notify();
+        }
+        ToolbarItemLocation::Hidden
+    }
+}
+
+mod persistence {
+    use db::{
+        query,
+        sqlez::{domain::Domain, thread_safe_connection::ThreadSafeConnection},
+        sqlez_macros::sql,
+    };
+    use workspace::{ItemId, WorkspaceDb, WorkspaceId};
+
+    pub struct OpenLogViewDb(ThreadSafeConnection);
+
+    impl Domain for OpenLogViewDb {
+        const NAME: &str = stringify!(OpenLogViewDb);
+
+        const MIGRATIONS: &[&str] = &[sql!(
+                CREATE TABLE open_log_views (
+                    workspace_id INTEGER,
+                    item_id INTEGER UNIQUE,
+
+                    state TEXT NOT NULL,
+
+                    PRIMARY KEY(workspace_id, item_id),
+                    FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                    ON DELETE CASCADE
+                ) STRICT;
+        )];
+    }
+
+    db::static_connection!(OpenLogViewDb, [WorkspaceDb]);
+
+    impl OpenLogViewDb {
+        query! {
+            pub async fn save_state(
+                item_id: ItemId,
+                workspace_id: WorkspaceId,
+                state: String
+            ) -> Result<()> {
+                INSERT OR REPLACE INTO open_log_views(item_id, workspace_id, state)
+                VALUES (?, ?, ?)
+            }
+        }
+
+        query! {
+            pub fn get_state(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<String>> {
+                SELECT state
+                FROM open_log_views
+                WHERE item_id = ? AND workspace_id = ?
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_log_lines() {
+        assert_eq!(
+            split_log_lines("a\nb\r\nc\rd").collect::<Vec<_>>(),
+            vec!["a", "b", "c", "d"]
+        );
+        assert_eq!(
+            split_log_lines("a\r\rb\n").collect::<Vec<_>>(),
+            vec!["a", "", "b"]
+        );
+        assert_eq!(split_log_lines("").count(), 0);
+    }
+
+    #[test]
+    fn test_complete_lines_len() {
+        assert_eq!(complete_lines_len(b"partial"), 0);
+        assert_eq!(complete_lines_len(b"a\nb"), 2);
+        assert_eq!(complete_lines_len(b"a\r\nb\rc"), 5);
+        // A trailing `\r` may be followed by `\n` in the next read.
+        assert_eq!(complete_lines_len(b"a\nb\r"), 2);
+        assert_eq!(complete_lines_len(b"a\r"), 0);
+    }
+
+    #[test]
+    fn test_split_log_content_with_record_separator() {
+        let record_separator = RecordSeparator::new(r"\n-{3,}\n".to_string()).unwrap();
+        let record_separator = Some(&record_separator);
+        let text = "first\n  detail\n---\nsecond\n-----\n\n---\nthird";
+        assert_eq!(
+            split_log_content(text, record_separator).collect::<Vec<_>>(),
+            vec!["first\n  detail", "second", "third"]
+        );
+        assert_eq!(
+            complete_content_len(text.as_bytes(), record_separator),
+            text.len() - "third".len()
+        );
+        assert_eq!(complete_content_len(b"unfinished", record_separator), 0);
+
+        let record = LogLine::from_record("first\n  detail\r\nmore");
+        assert_eq!(record.text.as_ref(), "first");
+        assert_eq!(
+            record
+                .continuation_lines
+                .iter()
+                .map(|line| line.as_ref())
+                .collect::<Vec<_>>(),
+            vec!["  detail", "more"]
+        );
+    }
+
+    #[test]
+    fn test_log_filter() {
+        let filter = |query: &str| {
+            let mut filter = LogFilter::new(false, LevelMask::ALL);
+            filter.set_query(query.to_string());
+            filter
+        };
+        let matches = |filter: &LogFilter, line: &str| filter.matches(line, &line.to_lowercase());
+
+        assert!(matches(&filter(""), "INFO anything"));
+        assert!(matches(&filter(""), ""));
+
+        assert!(matches(&filter("worker"), "INFO [Worker] started"));
+        assert!(matches(&filter("WORKER"), "INFO [worker] started"));
+
+        let terms = filter("worker started");
+        assert!(matches(&terms, "INFO [worker] started job"));
+        assert!(!matches(&terms, "INFO [worker] stopped"));
+        assert!(!matches(&terms, "INFO [lsp] started"));
+        assert!(!matches(
+            &filter("worker -lsp"),
+            "INFO [worker] waiting on lsp"
+        ));
+
+        let regex = filter("/work(er|s)/");
+        assert!(regex.regex_error.is_none());
+        assert!(matches(&regex, "INFO [WORKS] done"));
+        assert!(!matches(&regex, "INFO [workflow] done"));
+
+        let invalid_regex = filter("/[worker/");
+        assert!(invalid_regex.regex_error.is_some());
+        assert!(matches(&invalid_regex, "INFO queued [worker/1"));
+        assert!(!matches(&invalid_regex, "INFO worker done"));
+
+        let mut invalid_whole_word_regex = LogFilter::new(true, LevelMask::ALL);
+        invalid_whole_word_regex.set_query("/[worker/".to_string());
+        assert!(invalid_whole_word_regex.regex_error.is_some());
+        assert!(matches(&invalid_whole_word_regex, "INFO queued [worker/1"));
+        assert!(!matches(&invalid_whole_word_regex, "INFO worker done"));
+
+        let mut levels = filter("");
+        levels.level_filter = LevelMask::only(LogLevel::Error);
+        assert!(levels.matches_level("ERROR failed"));
+        assert!(!levels.matches_level("INFO started"));
+        assert!(levels.matches_level("no level at all"));
+    }
+
+    #[test]
+    fn test_lines_written_between_load_and_watch_are_read() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let log_path = temp_dir.path().join("Zed.log");
+        std::fs::write(&log_path, "INFO loaded\n").unwrap();
+        let loaded = std::fs::read(&log_path).unwrap();
+        let last_byte_offset = complete_content_len(&loaded, None);
+
+        // Written after the initial load but before the watcher starts, so no event reports it.
+        let mut log_file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&log_path)
+            .unwrap();
+        std::io::Write::write_all(&mut log_file, b"INFO missed\nINFO unfinished").unwrap();
+
+        let (appended, complete_len) =
+            smol::block_on(read_appended_content(&log_path, last_byte_offset, None)).unwrap();
+        assert_eq!(
+            split_log_content(&appended, None).collect::<Vec<_>>(),
+            vec!["INFO missed"]
+        );
+        assert_eq!(
+            last_byte_offset + complete_len,
+            "INFO loaded\nINFO missed\n".len()
+        );
+    }
+
+    #[test]
+    fn test_tail_preview_lines() {
+        assert_eq!(
+            tail_preview_lines(b"ial line\nINFO one\nINFO two\nINFO thr"),
+            b"INFO one\nINFO two\n"
+        );
+        assert_eq!(tail_preview_lines(b"INFO one\nINFO two\n"), b"INFO two\n");
+        assert_eq!(tail_preview_lines(b"no newline at all"), b"");
+    }
+
+    #[test]
+    fn test_valid_styled_runs() {
+        let runs = valid_styled_runs(
+            vec![
+                (7..9, "number"),
+                (0..4, "keyword"),
+                (3..3, "keyword"),
+                (5..6, "string"),
+                (8..20, "string"),
+            ],
+            "INFO é done",
+        );
+        assert_eq!(runs, vec![(0..4, "keyword"), (7..9, "number")]);
+    }
+
+    #[test]
+    fn test_log_rate() {
+        let mut log_rate = LogRate::new();
+        assert!(!log_rate.has_lines(0));
+
+        log_rate.record(3, 2, 10);
+        log_rate.record(3, 1, 12);
+        log_rate.record(5, 4, 13);
+        let line_counts = log_rate.line_counts(5);
+        assert_eq!(line_counts.len(), LOG_RATE_WINDOW_SECS as usize);
+        assert_eq!(line_counts[LOG_RATE_WINDOW_SECS as usize - 3..], [3, 0, 4]);
+        assert_eq!(line_counts.iter().sum::<usize>(), 7);
+        assert_eq!(log_rate.busiest_first_entry(5), Some(13));
+
+        assert!(log_rate.has_lines(3 + LOG_RATE_WINDOW_SECS));
+        assert_eq!(
+            log_rate.busiest_first_entry(3 + LOG_RATE_WINDOW_SECS),
+            Some(13)
+        );
+        assert!(!log_rate.has_lines(5 + LOG_RATE_WINDOW_SECS));
+
+        log_rate.record(5 + LOG_RATE_WINDOW_SECS, 1, 20);
+        assert_eq!(log_rate.buckets.len(), 1);
+    }
+
+    #[test]
+    fn test_time_gap() {
+        let threshold = TimeDelta::seconds(60);
+        assert_eq!(
+            time_gap(
+                "2024-05-01T10:00:00+00:00 INFO idle",
+                "2024-05-01T10:04:30+00:00 INFO woke",
+                threshold,
+            ),
+            Some(TimeDelta::seconds(270))
+        );
+        assert_eq!(
+            time_gap(
+                "2024-05-01T10:00:00+00:00 INFO one",
+                "2024-05-01T10:00:59+00:00 INFO two",
+                threshold,
+            ),
+            None
+        );
+        assert_eq!(
+            time_gap(
+                "INFO untimed",
+                "2024-05-01T10:04:30+00:00 INFO woke",
+                threshold
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_last_row_with_text() {
+        let content = "INFO started\nWARN slow\nINFO started\nERROR failed\n";
+        assert_eq!(last_row_with_text(content, "INFO started"), Some(2));
+        assert_eq!(last_row_with_text(content, "ERROR failed"), Some(3));
+        assert_eq!(last_row_with_text(content, "WARN"), None);
+    }
+
+    #[test]
+    fn test_trailing_entry_count_within() {
+        let entries = ["INFO one", "INFO two", "INFO three"].map(String::from);
+        assert_eq!(trailing_entry_count_within(&entries, 1000), 3);
+        // "INFO two\nINFO three" is 19 bytes.
+        assert_eq!(trailing_entry_count_within(&entries, 19), 2);
+        assert_eq!(trailing_entry_count_within(&entries, 18), 1);
+        assert_eq!(trailing_entry_count_within(&entries, 1), 1);
+        assert_eq!(trailing_entry_count_within(&[], 1000), 0);
+    }
+
+    #[test]
+    fn test_rotation_overlap_len() {
+        let old_lines = ["INFO one", "INFO two", "WARN three", "INFO four"];
+        let new_lines = ["WARN three", "INFO four", "INFO five"];
+        let overlap_len = rotation_overlap_len(&old_lines, &new_lines, 50);
+        assert_eq!(overlap_len, 2);
+        let lines = old_lines
+            .iter()
+            .chain(new_lines.iter().skip(overlap_len))
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [
+                "INFO one",
+                "INFO two",
+                "WARN three",
+                "INFO four",
+                "INFO five"
+            ]
+        );
+
+        assert_eq!(rotation_overlap_len(&old_lines, &new_lines, 1), 0);
+        assert_eq!(rotation_overlap_len(&old_lines, &new_lines, 0), 0);
+        assert_eq!(rotation_overlap_len(&old_lines, &["INFO five"], 50), 0);
+        assert_eq!(rotation_overlap_len(&old_lines, &[], 50), 0);
+        assert_eq!(rotation_overlap_len(&old_lines, &old_lines, 50), 4);
+    }
+
+    #[test]
+    fn test_step_filtered_index() {
+        let filtered_indices = [2, 5, 9, 14];
+        assert_eq!(step_filtered_index(&[], Some(2), 0, 1), None);
+        assert_eq!(
+            step_filtered_index(&filtered_indices, Some(5), 0, 1),
+            Some(2)
+        );
+        assert_eq!(
+            step_filtered_index(&filtered_indices, Some(5), 0, -1),
+            Some(0)
+        );
+        assert_eq!(
+            step_filtered_index(&filtered_indices, Some(2), 0, -1),
+            Some(0)
+        );
+        assert_eq!(
+            step_filtered_index(&filtered_indices, Some(9), 0, 10),
+            Some(3)
+        );
+        assert_eq!(
+            step_filtered_index(&filtered_indices, Some(14), 0, isize::MIN),
+            Some(0)
+        );
+        assert_eq!(
+            step_filtered_index(&filtered_indices, Some(2), 0, isize::MAX),
+            Some(3)
+        );
+        // Line 7 is hidden, between the entries at filtered indices 1 and 2.
+        assert_eq!(
+            step_filtered_index(&filtered_indices, Some(7), 0, 1),
+            Some(2)
+        );
+        assert_eq!(
+            step_filtered_index(&filtered_indices, Some(7), 0, -1),
+            Some(1)
+        );
+        assert_eq!(step_filtered_index(&filtered_indices, None, 1, 1), Some(1));
+        assert_eq!(step_filtered_index(&filtered_indices, None, 8, 1), Some(3));
+    }
+
+    #[test]
+    fn test_markdown_code_block() {
+        assert_eq!(
+            markdown_code_block("INFO one\nWARN two", "log"),
+            "```log\nINFO one\nWARN two\n```"
+        );
+        assert_eq!(markdown_code_block("INFO one", ""), "```\nINFO one\n```");
+        assert_eq!(
+            markdown_code_block("INFO got ```rust``` back", "log"),
+            "````log\nINFO got ```rust``` back\n````"
+        );
+    }
+
+    #[test]
+    fn test_parse_log_entries() {
+        let items = [
+            "  orphan",
+            "INFO first",
+            "  frame",
+            "WARN second",
+            "ERROR third",
+            "  frame",
+        ];
+        let entries = parse_log_entries(items.into_iter(), false, 3);
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (entry.text.as_ref(), entry.continuation_lines.len()))
+                .collect::<Vec<_>>(),
+            vec![("  orphan", 0), ("INFO first", 1), ("WARN second", 0)]
+        );
+
+        let entries = parse_log_entries(["a\n  b", "c"].into_iter(), true, 10);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].continuation_lines.len(), 1);
+        assert!(parse_log_entries(items.into_iter(), false, 0).is_empty());
+    }
+
+    #[test]
+    fn test_incremental_splitting_matches_full_load() {
+        let log = "one\r\ntwo\rthree\nfour\r\rfive\r\n";
+        let full_load = split_log_lines(log).collect::<Vec<_>>();
+
+        for chunk_len in 1..=log.len() {
+            let mut incremental = Vec::new();
+            let mut read_len = 0;
+            let mut written_len = 0;
+            while written_len < log.len() {
+                written_len = (written_len + chunk_len).min(log.len());
+                let appended = &log.as_bytes()[read_len..written_len];
+                let complete_len = complete_lines_len(appended);
+                let appended = String::from_utf8_lossy(&appended[..complete_len]);
+                incremental.extend(split_log_lines(&appended).map(str::to_string));
+                read_len += complete_len;
+            }
+            assert_eq!(incremental, full_load, "chunk length {chunk_len}");
+        }
+    }
+
+    #[test]
+    fn test_trim_line_indices() {
+        let cases: [(Vec<usize>, usize, usize, Vec<usize>); 5] = [
+            (vec![0, 2, 5], 0, 0, vec![0, 2, 5]),
+            (vec![0, 2, 5, 9], 3, 2, vec![2, 6]),
+            (vec![4, 7], 3, 0, vec![1, 4]),
+            (vec![1, 3, 4], 3, 2, vec![0, 1]),
+            (vec![0, 1, 2], 10, 3, vec![]),
+        ];
+        for (line_indices, dropped_line_count, expected_trimmed_count, expected) in cases {
+            let mut trimmed_indices = line_indices.clone();
+            assert_eq!(
+                trim_line_indices(&mut trimmed_indices, dropped_line_count),
+                expected_trimmed_count,
+                "{line_indices:?} dropping {dropped_line_count}",
+            );
+            assert_eq!(
+                trimmed_indices, expected,
+                "{line_indices:?} dropping {dropped_line_count}",
+            );
+        }
+    }
+
+    #[test]
+    fn test_truncate_line_indices() {
+        let mut line_indices = vec![0, 2, 5, 9];
+        assert_eq!(truncate_line_indices(&mut line_indices, 5), 2);
+        assert_eq!(line_indices, vec![0, 2]);
+
+        let mut line_indices = vec![0, 2];
+        assert_eq!(truncate_line_indices(&mut line_indices, 3), 2);
+        assert_eq!(line_indices, vec![0, 2]);
+
+        let mut line_indices = vec![0, 2];
+        assert_eq!(truncate_line_indices(&mut line_indices, 0), 0);
+        assert!(line_indices.is_empty());
+    }
+
+    #[test]
+    fn test_try_extract_json() {
+        let (prefix, json) =
+            try_extract_json(r#"INFO [worker] finished {"id": 7, "tags": ["a"]}  "#).unwrap();
+        assert_eq!(prefix, "INFO [worker] finished ");
+        assert_eq!(json, serde_json::json!({"id": 7, "tags": ["a"]}));
+
+        let (prefix, json) = try_extract_json("ERROR [lsp] ids: [1, 2]").unwrap();
+        assert_eq!(prefix, "ERROR [lsp] ids: ");
+        assert_eq!(json, serde_json::json!([1, 2]));
+
+        assert!(try_extract_json("INFO [worker] finished").is_none());
+        assert!(try_extract_json("INFO [worker] not json {id: 7}").is_none());
+    }
+
+    #[test]
+    fn test_json_tokens() {
+        let json = r#"{"key": "va\"lue", "n": -1.5e3, "ok": true, "none": null}"#;
+        let tokens = json_tokens(json)
+            .into_iter()
+            .map(|(range, token)| (&json[range], token))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tokens,
+            vec![
+                (r#""key""#, JsonToken::Key),
+                (r#""va\"lue""#, JsonToken::String),
+                (r#""n""#, JsonToken::Key),
+                ("-1.5e3", JsonToken::Number),
+                (r#""ok""#, JsonToken::Key),
+                ("true", JsonToken::Literal),
+                (r#""none""#, JsonToken::Key),
+                ("null", JsonToken::Literal),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_log_tokens() {
+        let line = r#"INFO opened "my file" at /tmp/zed/log.txt in 12ms (id=67e55044-10b1-426f-9247-bb680e5fe0c8, see https://zed.dev/docs) from crates/zed/src/main.rs:42: retry -3 of 0x1F"#;
+        let tokens = log_tokens(line)
+            .into_iter()
+            .map(|(range, token)| (&line[range], token))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tokens,
+            vec![
+                (r#""my file""#, LogToken::String),
+                ("/tmp/zed/log.txt", LogToken::Path),
+                ("12", LogToken::Number),
+                ("67e55044-10b1-426f-9247-bb680e5fe0c8", LogToken::Uuid),
+                ("crates/zed/src/main.rs:42", LogToken::Path),
+                ("-3", LogToken::Number),
+                ("0x1F", LogToken::Number),
+            ]
+        );
+        assert!(log_tokens("request/s in word2 and v1.2").is_empty());
+    }
+
+    #[test]
+    fn test_file_references() {
+        let text = "panicked at src/main.rs:12:5: oops (see /tmp/zed/log.txt:3.) v1.2:4 foo:7";
+        let references = file_references(text)
+            .into_iter()
+            .map(|(range, reference)| (&text[range], reference))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            references,
+            vec![
+                (
+                    "src/main.rs:12:5",
+                    FileReference {
+                        path: "src/main.rs".to_string(),
+                        row: 12,
+                        column: Some(5),
+                    }
+                ),
+                (
+                    "/tmp/zed/log.txt:3",
+                    FileReference {
+                        path: "/tmp/zed/log.txt".to_string(),
+                        row: 3,
+                        column: None,
+                    }
+                ),
+            ]
+        );
+        assert_eq!(
+            parse_file_reference("C:\\src\\main.rs:8"),
+            Some(FileReference {
+                path: "C:\\src\\main.rs".to_string(),
+                row: 8,
+                column: None,
+            })
+        );
+        assert_eq!(parse_file_reference("src/main.rs:0"), None);
+    }
+
+    #[test]
+    fn test_chars_past_limit() {
+        assert_eq!(chars_past_limit("short", 10), 0);
+        assert_eq!(chars_past_limit("0123456789ab", 10), 2);
+        // Multi-byte characters are counted once.
+        assert_eq!(chars_past_limit("ééééé", 5), 0);
+        assert_eq!(
+            clip_ranges(vec![(0..4, 'a'), (3..8, 'b'), (6..9, 'c')], 6),
+            vec![(0..4, 'a'), (3..6, 'b')]
+        );
+    }
+
+    #[test]
+    fn test_line_fields() {
+        let line = "INFO [worker] done (worker_id=70, status=ok) elapsed=5ms";
+        let fields = line_fields(line)
+            .map(|(range, key, value)| (&line[range], key, value))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                ("worker_id=70", "worker_id", "70"),
+                ("status=ok", "status", "ok"),
+                ("elapsed=5ms", "elapsed", "5ms"),
+            ]
+        );
+        assert_eq!(parse_field("worker_id=7"), Some(("worker_id", "7")));
+        assert_eq!(parse_field("=7"), None);
+        assert_eq!(parse_field("worker_id="), None);
+        assert_eq!(parse_field("a+b=c"), None);
+    }
+
+    #[test]
+    fn test_term_match_ranges() {
+        let text = "err: error in inference (err_code=3) ::lsp";
+        let match_count = |term, whole_word| term_match_ranges(text, term, whole_word).count();
+        assert_eq!(match_count("err", false), 3);
+        assert_eq!(match_count("err", true), 1);
+        assert_eq!(match_count("err_code", true), 1);
+        assert_eq!(match_count("::lsp", true), 1);
+        assert_eq!(match_count("inference", true), 1);
+        assert_eq!(match_count("infer", true), 0);
+    }
+
+    #[test]
+    fn test_log_message() {
+        assert_eq!(
+            log_message("2024-05-01T12:00:00+00:00 ERROR [worker] failed to start"),
+            "[worker] failed to start"
+        );
+        assert_eq!(log_message("[WARN] disk almost full"), "disk almost full");
+        assert_eq!(log_message("    at main.rs:3"), "at main.rs:3");
+        assert_eq!(log_message("no prefix here"), "no prefix here");
+    }
+
+    #[test]
+    fn test_target_counts() {
+        let line = LogLine::new;
+        let mut target_counts = TargetCounts::default();
+        for text in [
+            "INFO [project] a",
+            "INFO [worker] b",
+            "INFO [worker] c",
+            "INFO [editor] d",
+            "INFO no target",
+        ] {
+            target_counts.add(&line(text));
+        }
+        assert_eq!(
+            target_counts.most_frequent(2),
+            vec!["worker".to_string(), "editor".to_string()]
+        );
+
+        target_counts.remove(&line("INFO [worker] b"));
+        target_counts.remove(&line("INFO [worker] c"));
+        assert_eq!(
+            target_counts.most_frequent(5),
+            vec!["editor".to_string(), "project".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_parse_log_target() {
+        assert_eq!(
+            parse_log_target("2024-05-01T12:00:00+00:00 ERROR [project::lsp_store] failed"),
+            Some("project::lsp_store")
+        );
+        assert_eq!(parse_log_target("[ERROR] [worker] done"), Some("worker"));
+        assert_eq!(parse_log_target("[2024-05-01] INFO no target"), None);
+        assert_eq!(parse_log_target("INFO a b c [late] target"), None);
+        assert_eq!(excluded_term("-project::lsp"), Some("project::lsp"));
+        assert_eq!(excluded_term("-"), None);
+        assert_eq!(excluded_term("project"), None);
+    }
+
+    #[test]
+    fn test_trim_then_truncate_matches_full_recompute() {
+        // Lines 0..10 where even lines pass the filter. Dropping 3 lines from the front and
+        // re-testing from line 4 on (after the drop) should agree with rescanning everything.
+        let passes = |line_index: usize| line_index % 2 == 0;
+        let mut line_indices = (0..10).filter(|&ix| passes(ix)).collect::<Vec<_>>();
+        let dropped_line_count = 3;
+        let first_changed_line_index = 4;
+
+        trim_line_indices(&mut line_indices, dropped_line_count);
+        truncate_line_indices(&mut line_indices, first_changed_line_index);
+        line_indices.extend(
+            (first_changed_line_index..10 - dropped_line_count)
+                .filter(|&ix| passes(ix + dropped_line_count)),
+        );
+
+        let expected = (0..10 - dropped_line_count)
+            .filter(|&ix| passes(ix + dropped_line_count))
+            .collect::<Vec<_>>();
+        assert_eq!(line_indices, expected);
+    }
+}
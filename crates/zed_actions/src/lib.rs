@@ -268,6 +268,22 @@ pub mod remote_debug {
     );
 }
 
+pub mod open_log {
+    use gpui::Action;
+    use schemars::JsonSchema;
+    use serde::Deserialize;
+
+    /// Opens the log filtered to a query, scrolled to the first line matching it. Without a
+    /// query, the filter box is focused to type one.
+    #[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+    #[action(namespace = open_log)]
+    #[serde(deny_unknown_fields)]
+    pub struct OpenFiltered {
+        #[serde(default)]
+        pub query: String,
+    }
+}
+
 pub mod workspace {
     use gpui::actions;
 
@@ -0,0 +1,61 @@
+use std::ops::Range;
+use std::sync::{PoisonError, RwLock};
+
+/// A styled span of a log line, as shown by a log viewer such as Zed's log view.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StyledRun {
+    /// The byte range of the run within the line, with its escape sequences removed.
+    pub range: Range<usize>,
+    /// The name of the syntax theme style to apply, such as `"string"` or `"keyword"`.
+    pub style: &'static str,
+}
+
+type Colorizer = Box<dyn Fn(&str) -> Option<Vec<StyledRun>> + Send + Sync>;
+
+static COLORIZERS: RwLock<Vec<Colorizer>> = RwLock::new(Vec::new());
+
+/// Adds a colorizer for log lines, so a subsystem can have its lines highlighted its own way.
+/// Lines it returns `None` for are left to the colorizers added after it, and then to the
+/// viewer's default highlighting.
+pub fn register_colorizer(
+    colorizer: impl Fn(&str) -> Option<Vec<StyledRun>> + Send + Sync + 'static,
+) {
+    COLORIZERS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(Box::new(colorizer));
+}
+
+/// Returns the runs of the first colorizer, in the order they were added, that styles `line`.
+pub fn colorize(line: &str) -> Option<Vec<StyledRun>> {
+    COLORIZERS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .find_map(|colorizer| colorizer(line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_colorizer_styling_a_line_wins() {
+        let run = |range: Range<usize>, style| StyledRun { range, style };
+        register_colorizer(move |line| {
+            line.starts_with("[worker]")
+                .then(|| vec![run(0..8, "keyword")])
+        });
+        register_colorizer(move |line| line.contains('[').then(|| vec![run(0..1, "punctuation")]));
+
+        assert_eq!(
+            colorize("[worker] started"),
+            Some(vec![run(0..8, "keyword")])
+        );
+        assert_eq!(
+            colorize("[lsp] started"),
+            Some(vec![run(0..1, "punctuation")])
+        );
+        assert_eq!(colorize("no target"), None);
+    }
+}